@@ -0,0 +1,388 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use crate::day_09::Motion;
+use crate::day_14::map::{FieldContent, Map, SandStep};
+use crate::error::{Error, Result};
+use crate::point::{BoundingBox, Dir, Point};
+use crate::solution::{no_params, read_input, with_params};
+use crate::{day_09, day_10, day_14};
+
+/// A simulation that advances one step at a time, drawn as a character per point of the plane.
+pub trait Simulation {
+    /// Advances by one step; `false` once there is nothing left to simulate.
+    fn step(&mut self) -> Result<bool>;
+
+    /// The part of the plane with something to draw.
+    fn bounds(&self) -> BoundingBox;
+
+    /// The point the viewport follows.
+    fn focus(&self) -> Point;
+
+    fn cell(&self, p: Point) -> char;
+
+    /// A line of text shown under the picture, e.g. the answer so far.
+    fn status(&self) -> String;
+}
+
+pub struct Settings {
+    /// Frames per second; 0 draws the frames as fast as possible.
+    pub fps: f64,
+    /// Steps of the simulation between frames.
+    pub steps_per_frame: usize,
+    /// Size of the viewport in characters.
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings { fps: 30.0, steps_per_frame: 1, width: 80, height: 30 }
+    }
+}
+
+/// Runs the simulation to the end, redrawing the terminal on `out` after every `steps_per_frame` steps.
+pub fn animate(sim: &mut dyn Simulation, settings: &Settings, out: &mut impl Write) -> Result<()> {
+    // clear the screen once, then draw every frame over the previous one
+    write!(out, "\x1b[2J")?;
+    let mut running = true;
+    loop {
+        write!(out, "\x1b[H")?;
+        for line in frame(sim, settings.width, settings.height).lines() {
+            writeln!(out, "{line}\x1b[K")?;
+        }
+        write!(out, "\x1b[J")?;
+        out.flush()?;
+        if !running {
+            return Ok(());
+        }
+        if settings.fps > 0.0 {
+            thread::sleep(Duration::from_secs_f64(1.0 / settings.fps));
+        }
+        for _ in 0..settings.steps_per_frame.max(1) {
+            running = sim.step()?;
+            if !running {
+                break;
+            }
+        }
+    }
+}
+
+/// The viewport of at most `width` x `height` around the focus, with the status line under it.
+pub fn frame(sim: &dyn Simulation, width: usize, height: usize) -> String {
+    let view = viewport(sim.bounds(), sim.focus(), width, height);
+    let mut frame = String::new();
+    for y in view.y_range() {
+        frame.extend(view.x_range().map(|x| sim.cell(Point::new(x, y))));
+        frame.push('\n');
+    }
+    frame.push_str(&sim.status());
+    frame.push('\n');
+    frame
+}
+
+// as close to centered on the focus as the bounds allow
+fn viewport(bounds: BoundingBox, focus: Point, width: usize, height: usize) -> BoundingBox {
+    let span = |min: i64, max: i64, focus: i64, size: usize| {
+        let size = (size.max(1) as i64).min(max - min + 1);
+        let start = (focus - size / 2).clamp(min, max - size + 1);
+        (start, start + size - 1)
+    };
+    let (min_x, max_x) = span(bounds.min.x, bounds.max.x, focus.x, width);
+    let (min_y, max_y) = span(bounds.min.y, bounds.max.y, focus.y, height);
+    BoundingBox { min: Point::new(min_x, min_y), max: Point::new(max_x, max_y) }
+}
+
+/// The simulations each day can be animated with; the part picks the variant, see [`simulation`].
+pub const DAYS: [u8; 3] = [9, 10, 14];
+
+/// The simulation for `day` and `part` of the input at `input_path`:
+/// the rope with 2 or 10 knots on day 9, the CRT on day 10, and sand without or with the floor on day 14.
+/// `params` set the puzzle's parameters, such as the screen size or the sand source, as for [`crate::runner::run`].
+pub fn simulation(day: u8, part: u8, input_path: &str, params: &[(String, String)]) -> Result<Box<dyn Simulation>> {
+    if !DAYS.contains(&day) {
+        let days = DAYS.map(|d| d.to_string());
+        return Err(Error::expected(format!("a day with an animation: {}", days.join(", "))));
+    }
+    let located = |e: Error| e.in_day(day).in_input(input_path);
+    let input = read_input(input_path).map_err(|e| located(e.into()))?;
+    let input = input.as_bytes();
+    let sim: Result<Box<dyn Simulation>> = match day {
+        9 => {
+            no_params(day, params)?;
+            day_09::parse(input).map(|motions| {
+                let knots = if part == 1 { 2 } else { 10 };
+                Box::new(RopeSimulation::new(motions, knots)) as Box<dyn Simulation>
+            })
+        }
+        10 => {
+            let screen = with_params(day_10::Day10::default(), day, params)?;
+            day_10::parse(input).map(|ops| {
+                let crt = CrtSimulation::new(&ops, screen.screen_width.get(), screen.screen_height.get());
+                Box::new(crt) as Box<dyn Simulation>
+            })
+        }
+        _ => {
+            let source = with_params(day_14::Day14::default(), day, params)?.source;
+            day_14::parse::parse_points(input).and_then(|paths| {
+                let map = if part == 1 {
+                    day_14::parse::map(&paths, source)?
+                } else {
+                    day_14::parse::map_with_floor(&paths, source)?
+                };
+                Ok(Box::new(SandSimulation::new(map, source)) as Box<dyn Simulation>)
+            })
+        }
+    };
+    sim.map_err(located)
+}
+
+/// Day 14: each step moves the falling grain of sand by one cell.
+pub struct SandSimulation {
+    map: Map,
+    source: Point,
+    grain: Option<Point>,
+    resting: u32,
+    done: bool,
+}
+
+impl SandSimulation {
+    pub fn new(map: Map, source: Point) -> Self {
+        SandSimulation { map, source, grain: None, resting: 0, done: false }
+    }
+}
+
+impl Simulation for SandSimulation {
+    fn step(&mut self) -> Result<bool> {
+        if self.done {
+            return Ok(false);
+        }
+        match self.grain {
+            None if self.map.field(self.source)? == FieldContent::RestingSand => self.done = true,
+            None => self.grain = Some(self.source),
+            Some(p) => match self.map.sand_step(p) {
+                SandStep::Move(next) => self.grain = Some(next),
+                SandStep::Rest => {
+                    self.map.set_field(p, FieldContent::RestingSand)?;
+                    self.resting += 1;
+                    self.grain = None;
+                }
+                SandStep::Fall => {
+                    self.grain = None;
+                    self.done = true;
+                }
+            },
+        }
+        Ok(!self.done)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let (xs, ys) = (self.map.grid().x_range(), self.map.grid().y_range());
+        BoundingBox { min: Point::new(xs.start, ys.start), max: Point::new(xs.end - 1, ys.end - 1) }
+    }
+
+    fn focus(&self) -> Point {
+        self.grain.unwrap_or(self.source)
+    }
+
+    fn cell(&self, p: Point) -> char {
+        if Some(p) == self.grain {
+            return '~';
+        }
+        match self.map.field(p) {
+            _ if p == self.source => '+',
+            Ok(FieldContent::Rock) => '#',
+            Ok(FieldContent::RestingSand) => 'o',
+            _ => '.',
+        }
+    }
+
+    fn status(&self) -> String {
+        format!("{} units of sand at rest", self.resting)
+    }
+}
+
+/// Day 9: each step moves the head of the rope by one cell.
+pub struct RopeSimulation {
+    motions: Vec<Motion>,
+    next_motion: usize,
+    dir: Dir,
+    steps_left: u32,
+    rope: Vec<Point>,
+    visited: HashSet<Point>,
+    bounds: BoundingBox,
+}
+
+impl RopeSimulation {
+    pub fn new(motions: Vec<Motion>, knots: usize) -> Self {
+        let start = Point::default();
+        RopeSimulation {
+            motions,
+            next_motion: 0,
+            dir: Dir::Up,
+            steps_left: 0,
+            rope: vec![start; knots.max(1)],
+            visited: HashSet::from([start]),
+            bounds: BoundingBox::new(start),
+        }
+    }
+}
+
+impl Simulation for RopeSimulation {
+    fn step(&mut self) -> Result<bool> {
+        while self.steps_left == 0 {
+            match self.motions.get(self.next_motion) {
+                Some(&(dir, steps)) => (self.dir, self.steps_left) = (dir, steps),
+                None => return Ok(false),
+            }
+            self.next_motion += 1;
+        }
+        self.steps_left -= 1;
+        day_09::move_rope(&mut self.rope, self.dir);
+        self.visited.insert(self.rope[self.rope.len() - 1]);
+        for &knot in &self.rope {
+            self.bounds.include(knot);
+        }
+        Ok(true)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn focus(&self) -> Point {
+        self.rope[0]
+    }
+
+    fn cell(&self, p: Point) -> char {
+        let tail = self.rope.len() - 1;
+        match self.rope.iter().position(|&knot| knot == p) {
+            Some(0) => 'H',
+            Some(i) if i == tail && tail == 1 => 'T',
+            Some(i) => char::from_digit(i as u32 % 10, 10).unwrap(),
+            None if p == Point::default() => 's',
+            None if self.visited.contains(&p) => '#',
+            None => '.',
+        }
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "motion {} of {}, the tail visited {} positions",
+            self.next_motion, self.motions.len(), self.visited.len(),
+        )
+    }
+}
+
+/// Day 10: each step draws one pixel of the CRT, a screen of `width` x `height` pixels.
+pub struct CrtSimulation {
+    xs: Vec<i64>,
+    drawn: usize,
+    width: usize,
+    height: usize,
+}
+
+impl CrtSimulation {
+    pub fn new(ops: &[day_10::Op], width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        let mut xs = day_10::x_vals(ops);
+        xs.truncate(width * height);
+        CrtSimulation { xs, drawn: 0, width, height }
+    }
+
+    fn beam(&self) -> Point {
+        Point::new((self.drawn % self.width) as i64, (self.drawn / self.width) as i64)
+    }
+}
+
+impl Simulation for CrtSimulation {
+    fn step(&mut self) -> Result<bool> {
+        if self.drawn == self.xs.len() {
+            return Ok(false);
+        }
+        self.drawn += 1;
+        Ok(true)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox { min: Point::new(0, 0), max: Point::new(self.width as i64 - 1, self.height as i64 - 1) }
+    }
+
+    fn focus(&self) -> Point {
+        self.beam()
+    }
+
+    fn cell(&self, p: Point) -> char {
+        let i = p.y as usize * self.width + p.x as usize;
+        let sprite = |x: i64| p.x.abs_diff(x) <= 1;
+        if i < self.drawn {
+            if sprite(self.xs[i]) { '#' } else { '.' }
+        } else if i == self.drawn && i < self.xs.len() {
+            '@'
+        } else if p.y == self.beam().y && self.xs.get(self.drawn).is_some_and(|&x| sprite(x)) {
+            // the sprite ahead of the beam
+            '='
+        } else {
+            ' '
+        }
+    }
+
+    fn status(&self) -> String {
+        match self.xs.get(self.drawn) {
+            Some(x) => format!("cycle {}, X = {x}", self.drawn + 1),
+            None => format!("drew {} pixels", self.drawn),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(day: u8, part: u8) -> (Box<dyn Simulation>, String) {
+        let mut sim = simulation(day, part, &format!("example-{day:02}.txt"), &[]).unwrap();
+        let settings = Settings { fps: 0.0, steps_per_frame: 7, ..Settings::default() };
+        let mut out = vec![];
+        animate(sim.as_mut(), &settings, &mut out).unwrap();
+        (sim, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn simulations_reach_the_answers() {
+        assert_eq!(run(14, 1).0.status(), "24 units of sand at rest");
+        assert_eq!(run(14, 2).0.status(), "93 units of sand at rest");
+        assert_eq!(run(9, 1).0.status(), "motion 8 of 8, the tail visited 13 positions");
+        let (crt, out) = run(10, 2);
+        assert_eq!(crt.status(), "drew 240 pixels");
+        assert!(out.ends_with("drew 240 pixels\x1b[K\n\x1b[J"));
+        let picture = frame(crt.as_ref(), 40, 6).replace('.', " ");
+        let ops = day_10::parse(crate::solution::open_input("example-10.txt").unwrap()).unwrap();
+        assert_eq!(picture.lines().take(6).collect::<Vec<_>>(), day_10::drawing(&ops, 40, 6).lines().skip(1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn viewport_follows_the_focus_within_the_bounds() {
+        let bounds = BoundingBox { min: Point::new(0, 0), max: Point::new(99, 9) };
+        let view = viewport(bounds, Point::new(50, 5), 10, 4);
+        assert_eq!((view.min, view.max), (Point::new(45, 3), Point::new(54, 6)));
+        let view = viewport(bounds, Point::new(2, 9), 10, 40);
+        assert_eq!((view.min, view.max), (Point::new(0, 0), Point::new(9, 9)));
+        assert!(simulation(8, 1, "example-08.txt", &[]).is_err());
+    }
+
+    #[test]
+    fn simulations_take_the_puzzle_parameters() {
+        let param = |name: &str, value: &str| [(name.to_string(), value.to_string())];
+        let mut crt = simulation(10, 2, "example-10.txt", &param("screen_height", "2")).unwrap();
+        while crt.step().unwrap() {}
+        assert_eq!(crt.status(), "drew 80 pixels");
+        assert_eq!(crt.bounds().max, Point::new(39, 1));
+        let mut sand = simulation(14, 1, "example-14.txt", &param("source", "498,0")).unwrap();
+        while sand.step().unwrap() {}
+        assert_eq!(sand.status(), "2 units of sand at rest");
+        assert!(simulation(10, 2, "example-10.txt", &param("screen_width", "0")).is_err());
+        assert!(simulation(9, 1, "example-09.txt", &param("source", "498,0")).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Locate, Result};
+use crate::runner::default_input_path;
+use crate::solution::{read_input, registry};
+
+pub const DEFAULT_BASELINE_PATH: &str = "bench-baseline.txt";
+
+/// What is being timed: parsing the input, or solving one part from the parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        };
+        f.pad(name)
+    }
+}
+
+impl FromStr for Phase {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "parse" => Ok(Phase::Parse),
+            "part1" => Ok(Phase::Part1),
+            "part2" => Ok(Phase::Part2),
+            _ => Err(Error::expected("`parse`, `part1` or `part2`")),
+        }
+    }
+}
+
+/// Run times of one phase of one day, sorted from fastest to slowest.
+#[derive(Debug)]
+pub struct Timing {
+    pub day: u8,
+    pub phase: Phase,
+    pub samples: Vec<Duration>,
+}
+
+impl Timing {
+    fn new(day: u8, phase: Phase, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Timing { day, phase, samples }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn median(&self) -> Duration {
+        self.samples[self.samples.len() / 2]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples[self.samples.len() - 1]
+    }
+}
+
+fn time<T>(iterations: usize, mut f: impl FnMut() -> Result<T>) -> Result<(Vec<Duration>, T)> {
+    let mut samples = vec![];
+    let mut result = None;
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        let r = f()?;
+        samples.push(start.elapsed());
+        // dropping the result is not part of the measurement
+        result = Some(r);
+    }
+    Ok((samples, result.unwrap()))
+}
+
+/// Times parsing and both parts of every registered day (or just `day`)
+/// on its default input, `iterations` times each.
+pub fn bench(day: Option<u8>, iterations: usize) -> Result<Vec<Timing>> {
+    let mut timings = vec![];
+    for (&d, solution) in &registry() {
+        if day.is_some_and(|day| day != d) {
+            continue;
+        }
+        let input_path = default_input_path(d);
+        let located = |e: Error| e.in_day(d).in_input(&input_path);
+        let text = read_input(&input_path).map_err(|e| located(e.into()))?;
+        let (samples, input) = time(iterations, || solution.parse(&text)).map_err(located)?;
+        timings.push(Timing::new(d, Phase::Parse, samples));
+        for (part, phase) in [(1, Phase::Part1), (2, Phase::Part2)] {
+            let (samples, _) = time(iterations, || {
+                solution.part(part, input.as_ref()).expect("both parts are solved")
+            }).map_err(located)?;
+            timings.push(Timing::new(d, phase, samples));
+        }
+    }
+    Ok(timings)
+}
+
+/// Median run times saved from an earlier benchmark, to compare against.
+///
+/// The file has one line per day and phase: `DD PHASE NANOSECONDS`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, Phase), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Baseline> {
+        fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Baseline::parse(&text))
+            .map_err(|e| e.in_input(path))
+    }
+
+    pub fn parse(text: &str) -> Result<Baseline> {
+        let mut medians = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [day, phase, nanos] = fields[..] else {
+                return Err(Error::expected("`DAY PHASE NANOSECONDS`").at(i + 1, 1, line));
+            };
+            let day = day.parse::<u8>().at_token(i + 1, line, day)?;
+            let phase = phase.parse::<Phase>().at_token(i + 1, line, phase)?;
+            let nanos = nanos.parse::<u64>().at_token(i + 1, line, nanos)?;
+            medians.insert((day, phase), Duration::from_nanos(nanos));
+        }
+        Ok(Baseline { medians })
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Records the medians of `timings`, keeping the entries for other days and phases.
+    pub fn update(&mut self, timings: &[Timing]) {
+        for t in timings {
+            self.medians.insert((t.day, t.phase), t.median());
+        }
+    }
+
+    pub fn get(&self, day: u8, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(day, phase)).copied()
+    }
+
+    /// Relative change of the median against the baseline, e.g. `0.25` for 25% slower.
+    pub fn change(&self, timing: &Timing) -> Option<f64> {
+        self.get(timing.day, timing.phase)
+            .map(|base| timing.median().as_secs_f64() / base.as_secs_f64().max(1e-9) - 1.0)
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# DAY PHASE NANOSECONDS, median run times written by `aoc bench --save`")?;
+        for ((day, phase), median) in &self.medians {
+            writeln!(f, "{day:02} {phase} {}", median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_sorted_samples() {
+        let ms = Duration::from_millis;
+        let t = Timing::new(1, Phase::Part1, vec![ms(5), ms(1), ms(3)]);
+        assert_eq!((t.min(), t.median(), t.max()), (ms(1), ms(3), ms(5)));
+    }
+
+    #[test]
+    fn baseline_round_trip_and_change() {
+        let ms = Duration::from_millis;
+        let mut baseline = Baseline::default();
+        baseline.update(&[Timing::new(16, Phase::Part2, vec![ms(100)])]);
+        let baseline = Baseline::parse(&baseline.to_string()).unwrap();
+        assert_eq!(baseline.get(16, Phase::Part2), Some(ms(100)));
+        let slower = Timing::new(16, Phase::Part2, vec![ms(150)]);
+        assert!((baseline.change(&slower).unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(baseline.change(&Timing::new(1, Phase::Parse, vec![ms(1)])), None);
+    }
+}
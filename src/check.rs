@@ -0,0 +1,89 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::runner::panic_message;
+use crate::solution::{read_input, registry};
+
+/// Runs `check_line` on every line of `input`, with 1-based line numbers,
+/// collecting the errors instead of stopping at the first.
+pub fn check_lines(input: &str, mut check_line: impl FnMut(usize, &str) -> Result<()>) -> Vec<Error> {
+    input.lines()
+        .enumerate()
+        .filter_map(|(i, line)| check_line(i + 1, line).err())
+        .collect()
+}
+
+/// Receives each problem found by a parser that can carry on past malformed lines.
+/// Returning the error stops parsing, returning `Ok` skips the line.
+pub type Problem<'a> = dyn FnMut(Error) -> Result<()> + 'a;
+
+/// Runs such a parser, collecting every problem it reports.
+pub fn collect_problems<T>(parse: impl FnOnce(&mut Problem) -> Result<T>) -> Vec<Error> {
+    let mut problems = vec![];
+    let result = parse(&mut |e| {
+        problems.push(e);
+        Ok(())
+    });
+    problems.extend(result.err());
+    problems
+}
+
+/// Runs only the parser of `day` on the input at `input_path`, and returns every problem
+/// it finds. A panicking parser is reported as a problem too.
+///
+/// Fails only if there is no solution for `day` or the input cannot be read.
+pub fn check(day: u8, input_path: &str) -> Result<Vec<Error>> {
+    let registry = registry();
+    let solution = registry.get(&day).ok_or_else(|| Error::new(ErrorKind::NoSuchPuzzle(day, 1)))?;
+    let input = read_input(input_path).map_err(|e| Error::from(e).in_input(input_path))?;
+    let problems = panic::catch_unwind(AssertUnwindSafe(|| solution.check(&input)))
+        .unwrap_or_else(|payload| vec![Error::new(ErrorKind::Panic(panic_message(payload.as_ref())))]);
+    Ok(problems.into_iter().map(|e| e.in_day(day).in_input(input_path)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Locate;
+
+    use super::*;
+
+    fn problems(day: u8, input: &str) -> Vec<String> {
+        registry()[&day].check(input).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn days_report_every_bad_line() {
+        let crates = "[A] [B]\n[C] x\n 1   2\n\nmove 1 from 1 to 3\nmove x from 2 to 1\nmove 1 from 2 to 1\n";
+        assert_eq!(problems(5, crates), vec![
+            "2:5: expected a crate `[X]` or blanks",
+            "5:18: expected a stack number from 1 to 2",
+            "6:6: expected a number (invalid digit found in string)",
+        ]);
+        let monkeys = "Monkey 0:\n  Starting items: 1\n  Operation: new = old - 1\n  Test: divisible by 0\n\
+                       \x20   If true: throw to monkey 1\n    If false: throw to monkey 1\n\nMonkey 1:\n  Starting items: 1\n";
+        assert_eq!(problems(11, monkeys), vec![
+            "3:1: expected `Operation: new = old OP X`",
+            "4:1: expected a non-zero divisor",
+            "8:1: expected 6 lines describing monkey 1, then a blank line",
+        ]);
+        assert_eq!(problems(3, "abcaxy\nab1c\nabcd\n"), vec![
+            "2:3: expected an item `a`-`z` or `A`-`Z`",
+            "3:1: expected exactly one item type in both compartments",
+        ]);
+    }
+
+    #[test]
+    fn valves_out_of_reach_are_reported() {
+        let valves = "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+                      Valve BB has flow rate=5; tunnel leads to valve AA\n\
+                      Valve CC has flow rate=7; tunnel leads to valve DD\n\
+                      Valve DD has flow rate=0; tunnel leads to valve CC\n";
+        assert_eq!(problems(16, valves), vec!["3:1: expected a way from AA to valve CC, whose flow rate is 7"]);
+    }
+
+    #[test]
+    fn every_bad_line_is_reported() {
+        let problems = check_lines("1\nx\n3\ny\n", |i, line| line.parse::<u8>().map(drop).at_line(i, line));
+        assert_eq!(problems.iter().map(|e| e.line).collect::<Vec<_>>(), vec![Some(2), Some(4)]);
+    }
+}
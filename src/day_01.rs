@@ -1,60 +1,107 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+//! Day 1: Calorie Counting.
 
-fn max_calories() -> io::Result<u64> {
-    let mut max: u64 = 0;
-    let mut curr: u64 = 0;
-    let file = File::open("input-01.txt")?;
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(l) = line {
-            if l.is_empty() {
-                if curr > max {
-                    max = curr;
-                }
-                curr = 0;
-            } else {
-                curr += l.parse::<u64>().unwrap();
-            }
-        }
-    }
-    if curr > max {
-        max = curr;
+use std::io::BufRead;
+
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::solution::{Answer, Solution};
+
+// `None` for the blank line after each elf's items
+fn parse_calories(line_no: usize, line: &str) -> Result<Option<u64>> {
+    if line.is_empty() {
+        Ok(None)
+    } else {
+        line.parse::<u64>().map(Some).at_line(line_no, line)
     }
-    Ok(max)
 }
 
-fn top_three_calories() -> io::Result<u64> {
+pub fn parse(input: impl BufRead) -> Result<Vec<u64>> {
     let mut totals: Vec<u64> = Vec::new();
     let mut curr: u64 = 0;
-    let file = File::open("input-01.txt")?;
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(l) = line {
-            if l.is_empty() {
+    for (i, line) in input.lines().enumerate() {
+        let l = line?;
+        match parse_calories(i + 1, &l)? {
+            Some(calories) => curr += calories,
+            None => {
                 totals.push(curr);
                 curr = 0;
-            } else {
-                curr += l.parse::<u64>().unwrap();
             }
         }
     }
     totals.push(curr);
+    Ok(totals)
+}
+
+pub fn max_calories(totals: &[u64]) -> u64 {
+    totals.iter().max().unwrap_or(&0).to_owned()
+}
+
+pub fn top_three_calories(totals: &[u64]) -> Result<u64> {
+    if totals.len() < 3 {
+        return Err(Error::no_answer("fewer than three elves"));
+    }
+    let mut totals = totals.to_vec();
     totals.sort();
     totals.reverse();
-    let top3_sum = totals[0] + totals[1] + totals[2];
-    Ok(top3_sum)
+    Ok(totals[0] + totals[1] + totals[2])
+}
+
+/// The `n` elves carrying the most calories, numbered from 1, with their totals, most first.
+pub fn top_elves(totals: &[u64], n: usize) -> Vec<(usize, u64)> {
+    let mut elves = totals.iter().copied().enumerate().map(|(i, total)| (i + 1, total)).collect::<Vec<_>>();
+    elves.sort_by_key(|&(elf, total)| (std::cmp::Reverse(total), elf));
+    elves.truncate(n);
+    elves
+}
+
+fn elves_witness(elves: &[(usize, u64)]) -> String {
+    elves.iter().map(|(elf, total)| format!("elf {elf}: {total} calories")).collect::<Vec<_>>().join("\n")
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<u64>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, totals: &Self::Input) -> Result<Answer> {
+        Ok(max_calories(totals).into())
+    }
+
+    fn part2(&self, totals: &Self::Input) -> Result<Answer> {
+        top_three_calories(totals).map(Answer::from)
+    }
+
+    fn explain1(&self, totals: &Self::Input) -> Result<(Answer, Option<String>)> {
+        Ok((max_calories(totals).into(), Some(elves_witness(&top_elves(totals, 1)))))
+    }
+
+    fn explain2(&self, totals: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let answer = top_three_calories(totals)?;
+        Ok((answer.into(), Some(elves_witness(&top_elves(totals, 3)))))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse_calories(i, line).map(drop))
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_max_calories() {
-        println!("{}", max_calories().unwrap());
+        println!("{}", max_calories(&parse(open_input("input-01.txt").unwrap()).unwrap()));
     }
 
     #[test]
     fn print_top_three_calories() {
-        println!("{}", top_three_calories().unwrap());
+        println!("{}", top_three_calories(&parse(open_input("input-01.txt").unwrap()).unwrap()).unwrap());
     }
 }
@@ -1,8 +1,13 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+//! Day 2: Rock Paper Scissors.
+
+use std::io::BufRead;
 use std::str::FromStr;
 
-mod rps {
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::solution::{Answer, Solution};
+
+pub mod rps {
     #[derive(Clone, Copy)]
     pub enum Move {
         Rock,
@@ -35,9 +40,9 @@ mod rps {
 use rps::Move::*;
 
 impl FromStr for rps::Move {
-    type Err = String;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "A" => Ok(Rock),
             "B" => Ok(Paper),
@@ -45,47 +50,65 @@ impl FromStr for rps::Move {
             "X" => Ok(Rock),
             "Y" => Ok(Paper),
             "Z" => Ok(Scissors),
-            _ => Err(String::from(s)),
+            _ => Err(Error::expected("a move `A`, `B`, `C`, `X`, `Y` or `Z`")),
         }
     }
 }
 
-fn score_with_assumption() -> io::Result<u64> {
-    let file = File::open("input-02.txt")?;
+// the second column means a move in part 1 and an intended result in part 2, so keep both
+pub type Round = (rps::Move, rps::Move, RoundResult);
+
+fn parse_round(line_no: usize, l: &str) -> Result<Round> {
+    let mut parts = l.split_whitespace();
+    let mut next_part = || {
+        parts.next().ok_or_else(|| Error::expected("two codes, e.g. `A Y`").at(line_no, l.len() + 1, l))
+    };
+    let first = next_part()?;
+    let second = next_part()?;
+    let their_move = first.parse::<rps::Move>().at_token(line_no, l, first)?;
+    let my_move = second.parse::<rps::Move>().at_token(line_no, l, second)?;
+    let intended_result = second.parse::<RoundResult>().at_token(line_no, l, second)?;
+    Ok((their_move, my_move, intended_result))
+}
+
+pub fn parse(input: impl BufRead) -> Result<Vec<Round>> {
+    let mut rounds = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        rounds.push(parse_round(i + 1, &line?)?);
+    }
+    Ok(rounds)
+}
+
+pub fn score_with_assumption(rounds: &[Round]) -> u64 {
     let mut total: u64 = 0;
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(l) = line {
-            let mut parts = l.split_whitespace();
-            let mut next_move = || { parts.next().unwrap().parse::<rps::Move>().unwrap() };
-            let their_move = next_move();
-            let my_move = next_move();
-            total += rps::my_score(my_move, their_move);
-        }
+    for &(their_move, my_move, _) in rounds {
+        total += rps::my_score(my_move, their_move);
     }
-    Ok(total)
+    total
 }
 
-enum RoundResult {
+#[derive(Clone, Copy)]
+pub enum RoundResult {
     Win,
     Draw,
     Lose,
 }
 
 impl FromStr for RoundResult {
-    type Err = String;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         use RoundResult::*;
         match s {
             "X" => Ok(Lose),
             "Y" => Ok(Draw),
             "Z" => Ok(Win),
-            _ => Err(String::from(s))
+            _ => Err(Error::expected("a result `X`, `Y` or `Z`"))
         }
     }
 }
 
-fn choose_move(their_move: rps::Move, intended_result: RoundResult) -> rps::Move {
+pub fn choose_move(their_move: rps::Move, intended_result: RoundResult) -> rps::Move {
     use RoundResult::*;
     match their_move {
         Rock => match intended_result {
@@ -106,33 +129,50 @@ fn choose_move(their_move: rps::Move, intended_result: RoundResult) -> rps::Move
     }
 }
 
-fn score_with_full_info() -> io::Result<u64> {
-    let file = File::open("input-02.txt")?;
+pub fn score_with_full_info(rounds: &[Round]) -> u64 {
     let mut total: u64 = 0;
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(l) = line {
-            let mut parts = l.split_whitespace();
-            let their_move = parts.next().unwrap().parse::<rps::Move>().unwrap();
-            let intended_result = parts.next().unwrap().parse::<RoundResult>().unwrap();
-            let my_move = choose_move(their_move, intended_result);
-            total += rps::my_score(my_move, their_move);
-        }
+    for &(their_move, _, intended_result) in rounds {
+        let my_move = choose_move(their_move, intended_result);
+        total += rps::my_score(my_move, their_move);
     }
-    Ok(total)
+    total
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Round>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, rounds: &Self::Input) -> Result<Answer> {
+        Ok(score_with_assumption(rounds).into())
+    }
+
+    fn part2(&self, rounds: &Self::Input) -> Result<Answer> {
+        Ok(score_with_full_info(rounds).into())
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse_round(i, line).map(drop))
+    }
+}
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_score_with_assumption() {
-        println!("{}", score_with_assumption().unwrap());
+        println!("{}", score_with_assumption(&parse(open_input("input-02.txt").unwrap()).unwrap()));
     }
 
     #[test]
     fn print_score_with_full_info() {
-        println!("{}", score_with_full_info().unwrap());
+        println!("{}", score_with_full_info(&parse(open_input("input-02.txt").unwrap()).unwrap()));
     }
 }
@@ -1,73 +1,145 @@
+//! Day 3: Rucksack Reorganization.
+
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
+
+use crate::check::check_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 
-fn priority(c: char) -> Result<u64, char> {
+pub fn priority(c: char) -> Result<u64> {
     if c.is_ascii_lowercase() {
-        Ok(((c as u8) - ('a' as u8) + 1) as u64)
+        Ok(((c as u8) - b'a' + 1) as u64)
     } else if c.is_ascii_uppercase() {
-            Ok(((c as u8) - ('A' as u8) + 27) as u64)
+            Ok(((c as u8) - b'A' + 27) as u64)
     } else {
-        Err(c)
+        Err(Error::expected("an item `a`-`z` or `A`-`Z`"))
+    }
+}
+
+fn check_items(line_no: usize, line: &str) -> Result<()> {
+    match line.chars().position(|c| !c.is_ascii_alphabetic()) {
+        Some(col) => Err(Error::expected("an item `a`-`z` or `A`-`Z`").at(line_no, col + 1, line)),
+        None => Ok(()),
+    }
+}
+
+pub fn parse(input: impl BufRead) -> Result<Vec<String>> {
+    let mut rucksacks = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let l = line?;
+        check_items(i + 1, &l)?;
+        rucksacks.push(l);
+    }
+    Ok(rucksacks)
+}
+
+// the item type in both compartments of the rucksack on line `line_no`
+fn common_item(line_no: usize, l: &str) -> Result<char> {
+    if !l.len().is_multiple_of(2) {
+        return Err(Error::expected("an even number of items").at(line_no, l.len(), l));
+    }
+    let compartment_size = l.len() / 2;
+    let c1: HashSet<char> = HashSet::from_iter(l[0..compartment_size].chars());
+    let c2: HashSet<char> = HashSet::from_iter(l[compartment_size..l.len()].chars());
+    let mut intersection = c1.intersection(&c2);
+    match (intersection.next(), intersection.next()) {
+        (Some(c), None) => Ok(*c),
+        _ => Err(Error::expected("exactly one item type in both compartments").at(line_no, 1, l)),
     }
 }
 
-fn compartment_overlap() -> io::Result<u64> {
+pub fn compartment_overlap(rucksacks: &[String]) -> Result<u64> {
     let mut sum: u64 = 0;
-    let file = File::open("input-03.txt")?;
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(l) = line {
-            let compartment_size = l.len() / 2;
-            let c1: HashSet<char> = HashSet::from_iter(l.get(0..compartment_size).unwrap().chars());
-            let c2: HashSet<char> = HashSet::from_iter(l.get(compartment_size..l.len()).unwrap().chars());
-            let mut intersection = c1.intersection(&c2);
-            let common_elem = intersection.next().unwrap().clone();
-            assert!(intersection.next().is_none());
-            sum += priority(common_elem).unwrap();
-        }
+    for (i, l) in rucksacks.iter().enumerate() {
+        sum += priority(common_item(i + 1, l)?)?;
     }
     Ok(sum)
 }
 
-fn intersection(team: &Vec<String>) -> Result<char, String> {
+fn intersection(team: &[String]) -> Option<char> {
     let common_chars = team.iter()
         .map(|i| HashSet::<char>::from_iter(i.chars()))
         .reduce(|acc, item| {
             acc.intersection(&item).map(|c| c.to_owned()).collect::<HashSet<char>>()
-        })
-        .unwrap();
-    assert_eq!(common_chars.len(), 1);
-    Ok(common_chars.iter().next().unwrap().to_owned())
+        })?;
+    if common_chars.len() == 1 {
+        common_chars.iter().next().copied()
+    } else {
+        None
+    }
+}
+
+const TEAM_SIZE: usize = 3;
+
+fn teams(rucksacks: &[String]) -> Result<impl Iterator<Item = (usize, &[String])>> {
+    if !rucksacks.len().is_multiple_of(TEAM_SIZE) {
+        return Err(Error::expected(format!("the number of rucksacks to be a multiple of {TEAM_SIZE}")));
+    }
+    Ok(rucksacks.chunks_exact(TEAM_SIZE).enumerate())
+}
+
+fn badge(team_no: usize, team: &[String]) -> Result<char> {
+    intersection(team).ok_or_else(|| {
+        Error::expected("exactly one item type common to the team").at(team_no * TEAM_SIZE + 1, 1, &team[0])
+    })
 }
 
-fn team_overlap() -> io::Result<u64> {
+pub fn team_overlap(rucksacks: &[String]) -> Result<u64> {
     let mut sum: u64 = 0;
-    let file = File::open("input-03.txt")?;
-    const TEAM_SIZE: usize = 3;
-    let mut team: Vec<String> = (0..TEAM_SIZE).map(|_| String::new()).collect();
-    let mut i: usize = 0;
-    for line in io::BufReader::new(file).lines().map(|l| l.unwrap()) {
-        team[i] = line;
-        i += 1;
-        if i == TEAM_SIZE {
-            sum += priority(intersection(&team).unwrap()).unwrap();
-            i = 0;
-        }
-   }
+    for (t, team) in teams(rucksacks)? {
+        sum += priority(badge(t, team)?)?;
+    }
     Ok(sum)
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, rucksacks: &Self::Input) -> Result<Answer> {
+        compartment_overlap(rucksacks).map(Answer::from)
+    }
+
+    fn part2(&self, rucksacks: &Self::Input) -> Result<Answer> {
+        team_overlap(rucksacks).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        let mut problems = check_lines(input, |i, line| {
+            check_items(i, line)?;
+            common_item(i, line).map(drop)
+        });
+        if problems.is_empty() {
+            // the teams can only be checked once the rucksacks are known to be well-formed
+            let rucksacks = input.lines().map(str::to_string).collect::<Vec<_>>();
+            match teams(&rucksacks) {
+                Ok(teams) => problems.extend(teams.filter_map(|(t, team)| badge(t, team).err())),
+                Err(e) => problems.push(e),
+            };
+        }
+        problems
+    }
+}
+
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_compartment_overlap() {
-        println!("{}", compartment_overlap().unwrap());
+        println!("{}", compartment_overlap(&parse(open_input("input-03.txt").unwrap()).unwrap()).unwrap());
     }
 
     #[test]
     fn print_team_overlap() {
-        println!("{}", team_overlap().unwrap());
+        println!("{}", team_overlap(&parse(open_input("input-03.txt").unwrap()).unwrap()).unwrap());
     }
 }
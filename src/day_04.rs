@@ -1,87 +1,109 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::num::ParseIntError;
-use std::ops::Range;
+//! Day 4: Camp Cleanup.
 
-use crate::day_04::Error::*;
+use std::io::BufRead;
+use std::ops::Range;
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    NotEnoughLineParts,
-    NotEnoughRangeParts,
-    ParseError(ParseIntError),
-}
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::intervals::IntervalSet;
+use crate::solution::{Answer, Solution};
 
-fn parse_range(s: &str) -> Result<Range<u64>, Error> {
+// `s` is a slice of the line, used to locate errors
+fn parse_range(line_no: usize, line: &str, s: &str) -> Result<Range<u64>> {
+    let expected_range = || Error::expected("a range `A-B`").at_token(line_no, line, s);
     let mut parts = s.split("-");
     let mut next_num = || {
-        parts.next().ok_or(NotEnoughRangeParts)
-            .and_then(|s| s.parse::<u64>().map_err(|e| ParseError(e)))
+        parts.next().ok_or_else(expected_range)
+            .and_then(|n| n.parse::<u64>().at_token(line_no, line, n))
     };
     let start = next_num()?;
     let end = next_num()?;
-    assert!(parts.next().is_none());
+    if parts.next().is_some() || start > end {
+        return Err(expected_range());
+    }
     Ok(start..end + 1)
 }
 
-fn is_subrange(sub: &Range<u64>, sup: &Range<u64>) -> bool {
-    sup.contains(&sub.start) && sup.contains(&(sub.end - 1))
+pub type Pair = (Range<u64>, Range<u64>);
+
+fn parse_pair(line_no: usize, line: &str) -> Result<Pair> {
+    let expected_pair = || Error::expected("a pair of ranges `A-B,C-D`").at(line_no, 1, line);
+    let mut parts = line.split(",");
+    let mut next_range = || {
+        parts.next().ok_or_else(expected_pair)
+            .and_then(|s| parse_range(line_no, line, s))
+    };
+    let r1 = next_range()?;
+    let r2 = next_range()?;
+    if parts.next().is_some() {
+        return Err(expected_pair());
+    }
+    Ok((r1, r2))
+}
+
+pub fn parse(input: impl BufRead) -> Result<Vec<Pair>> {
+    let mut pairs = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        pairs.push(parse_pair(i + 1, &line?)?);
+    }
+    Ok(pairs)
 }
 
-fn fully_contains_count() -> io::Result<u64> {
+pub fn fully_contains_count(pairs: &[Pair]) -> u64 {
     let mut count: u64 = 0;
-    let file = File::open("input-04.txt")?;
-    for line in io::BufReader::new(file).lines().map(|l| l.unwrap()) {
-        let mut parts = line.split(",");
-        let mut next_range = || { parse_range(parts.next().unwrap()).unwrap() };
-        let r1 = next_range();
-        let r2 = next_range();
-        assert!(parts.next().is_none());
-        if is_subrange(&r1, &r2) || is_subrange(&r2, &r1) {
+    for (r1, r2) in pairs {
+        if IntervalSet::from(r2.clone()).contains_range(r1) || IntervalSet::from(r1.clone()).contains_range(r2) {
             count += 1;
         }
     }
-    Ok(count)
-}
-
-fn overlaps(r1: &Range<u64>, r2: &Range<u64>) -> bool {
-    r1.contains(&r2.start) ||
-        r1.contains(&(r2.end - 1)) ||
-        is_subrange(r1, r2)
+    count
 }
 
-fn overlaps_count() -> Result<u64, Error> {
+pub fn overlaps_count(pairs: &[Pair]) -> u64 {
     let mut count: u64 = 0;
-    let file = File::open("input-04.txt").map_err(|e| IO(e))?;
-    for line_or_error in io::BufReader::new(file).lines() {
-        let line = line_or_error.map_err(|e| IO(e))?;
-        let mut parts = line.split(",");
-        let mut next_range = || {
-            parts.next().ok_or(NotEnoughLineParts)
-                .and_then(|s| parse_range(s))
-        };
-        let r1 = next_range()?;
-        let r2 = next_range()?;
-        assert!(parts.next().is_none());
-        if overlaps(&r1, &r2) {
+    for (r1, r2) in pairs {
+        if IntervalSet::from(r1.clone()).overlaps(r2) {
             count += 1;
         }
     }
-    Ok(count)
+    count
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Pair>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, pairs: &Self::Input) -> Result<Answer> {
+        Ok(fully_contains_count(pairs).into())
+    }
+
+    fn part2(&self, pairs: &Self::Input) -> Result<Answer> {
+        Ok(overlaps_count(pairs).into())
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse_pair(i, line).map(drop))
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_fully_contains_count() {
-        println!("{}", fully_contains_count().unwrap());
+        println!("{}", fully_contains_count(&parse(open_input("input-04.txt").unwrap()).unwrap()));
     }
 
     #[test]
     fn print_overlaps_count() {
-        println!("{}", overlaps_count().unwrap());
+        println!("{}", overlaps_count(&parse(open_input("input-04.txt").unwrap()).unwrap()));
     }
 }
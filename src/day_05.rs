@@ -1,25 +1,16 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::num::ParseIntError;
-use std::str::FromStr;
+//! Day 5: Supply Stacks.
 
-use regex::Regex;
+use std::io::BufRead;
 
 use stacks::*;
 
-use crate::day_05::Error::*;
+use crate::check::{collect_problems, Problem};
+use crate::error::{Error, Locate, Result};
+use crate::solution::{params, Answer, Solution};
+use crate::trace::event;
 
-#[derive(Debug)]
-pub enum Error {
-    IO(io::Error),
-    NotEnoughCrates(usize),
-    MalformedMoveDesc(String),
-    ParseInt(ParseIntError),
-}
-
-mod stacks {
-    use crate::day_05::Error;
-    use crate::day_05::Error::NotEnoughCrates;
+pub mod stacks {
+    use crate::error::{Error, Result};
 
     pub type Stack = Vec<char>;
     pub type Stacks = Vec<Stack>;
@@ -31,17 +22,22 @@ mod stacks {
         pub to_stack: usize,
     }
 
-    pub fn make_move(stacks: &mut Stacks, mov: Move) -> Result<(), Error> {
+    fn not_enough_crates(stack: usize) -> Error {
+        Error::no_answer(format!("not enough crates on stack {}", stack + 1))
+    }
+
+    pub fn make_move(stacks: &mut Stacks, mov: &Move) -> Result<()> {
         for _ in 0..mov.num_crates {
-            let c = stacks[mov.from_stack].pop().ok_or(NotEnoughCrates(mov.from_stack))?;
+            let c = stacks[mov.from_stack].pop().ok_or_else(|| not_enough_crates(mov.from_stack))?;
             stacks[mov.to_stack].push(c);
         }
         Ok(())
     }
 
-    pub fn make_move_9001(stacks: &mut Stacks, mov: Move) -> Result<(), Error> {
+    pub fn make_move_9001(stacks: &mut Stacks, mov: &Move) -> Result<()> {
         let from = &mut stacks[mov.from_stack];
-        let from_num_remaining = from.len() - mov.num_crates;
+        let from_num_remaining = from.len().checked_sub(mov.num_crates)
+            .ok_or_else(|| not_enough_crates(mov.from_stack))?;
         let crates: Vec<char> = from.iter()
             .skip(from_num_remaining)
             .map(|c| c.to_owned())
@@ -70,97 +66,173 @@ enum InputSection {
     Moves,
 }
 
-fn parse_crates(line: &String, num_stacks: usize) -> Result<Vec<Option<char>>, Error> {
+// the crates are in every 4th column: `[A] [B]`
+fn parse_crates(line_no: usize, line: &str, num_stacks: usize) -> Result<Vec<Option<char>>> {
     let chars = line.chars().collect::<Vec<char>>();
+    if chars.len() > num_stacks * 4 {
+        return Err(Error::expected(format!("at most {num_stacks} stacks")).at(line_no, num_stacks * 4 + 1, line));
+    }
     let mut result = (0..num_stacks).map(|_| None).collect::<Vec<Option<char>>>();
-    for i in 0..num_stacks {
-        let crate_char = chars[i * 4 + 1];
-        // TODO: sanity check crate_char
-        if crate_char != ' ' {
-            result[i] = Some(crate_char);
+    for (i, crate_desc) in chars.chunks(4).enumerate() {
+        // trailing blanks may be missing
+        match crate_desc {
+            ['[', c, ']', ..] if c.is_ascii_uppercase() => result[i] = Some(*c),
+            [' ', ' ', ' ', ..] | [' ', ' '] | [' '] => (),
+            _ => return Err(Error::expected("a crate `[X]` or blanks").at(line_no, i * 4 + 1, line))
         }
     }
     Ok(result)
 }
 
-impl FromStr for Move {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // TODO: do not compile the regex on every invocation
-        let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-        let cap = re.captures(s).ok_or(MalformedMoveDesc(String::from(s)))?;
-        let parse_int = |i: usize| { cap[i].parse::<usize>().map_err(|e| ParseInt(e)) };
-        let num_crates = parse_int(1)?;
-        // we use base-0 stack indices but the description is base-1
-        let from_stack = parse_int(2)? - 1;
-        let to_stack = parse_int(3)? - 1;
-        Ok(Move { num_crates, from_stack, to_stack })
+fn parse_move(line_no: usize, line: &str, num_stacks: usize) -> Result<Move> {
+    let expected = || Error::expected("`move N from A to B`");
+    let mut tokens = line.split(' ');
+    let mut keyword = |kw: &str| match tokens.next() {
+        Some(token) if token == kw => tokens.next().ok_or_else(|| expected().at(line_no, line.len() + 1, line)),
+        Some(token) => Err(expected().at_token(line_no, line, token)),
+        None => Err(expected().at(line_no, line.len() + 1, line)),
+    };
+    let num_crates = keyword("move")?;
+    let from_stack = keyword("from")?;
+    let to_stack = keyword("to")?;
+    if let Some(token) = tokens.next() {
+        return Err(expected().at_token(line_no, line, token));
     }
+    // we use base-0 stack indices but the description is base-1
+    let parse_stack = |token: &str| {
+        match token.parse::<usize>().at_token(line_no, line, token)? {
+            n @ 1.. if n <= num_stacks => Ok(n - 1),
+            _ => Err(Error::expected(format!("a stack number from 1 to {num_stacks}")).at_token(line_no, line, token))
+        }
+    };
+    Ok(Move {
+        num_crates: num_crates.parse::<usize>().at_token(line_no, line, num_crates)?,
+        from_stack: parse_stack(from_stack)?,
+        to_stack: parse_stack(to_stack)?,
+    })
 }
 
-fn parse(input_file_path: &str) -> Result<(Stacks, Vec<Move>), Error> {
-    // cheating a bit
-    const NUM_STACKS: usize = 9;
+// Every malformed line is reported to `problem`, which can stop parsing by returning the error,
+// or carry on.
+fn parse_lines(input: impl BufRead, max_stacks: usize, problem: &mut Problem) -> Result<(Stacks, Vec<Move>)> {
     use InputSection::*;
     let mut section = Crates;
-    let mut stacks: Stacks = mk_stacks(NUM_STACKS);
+    let mut stacks: Stacks = mk_stacks(max_stacks);
     let mut moves: Vec<Move> = Vec::new();
-    let file = File::open(input_file_path).map_err(|e| IO(e))?;
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
         match section {
             Crates => {
                 if line.contains('[') {
-                    let level = parse_crates(&line, NUM_STACKS)?;
-                    for i in 0..NUM_STACKS {
-                        match level[i] {
-                            Some(crate_char) => add_crate_bottom(&mut stacks, i, crate_char),
-                            None => ()
+                    match parse_crates(i + 1, &line, max_stacks) {
+                        Ok(level) => {
+                            for (stack, crate_char) in level.into_iter().enumerate() {
+                                if let Some(crate_char) = crate_char {
+                                    add_crate_bottom(&mut stacks, stack, crate_char)
+                                }
+                            }
                         }
+                        Err(e) => problem(e)?,
                     }
                 } else {
+                    let num_stacks = line.split_whitespace().count();
+                    if stacks[num_stacks.min(max_stacks)..].iter().any(|s| !s.is_empty()) {
+                        problem(Error::expected("a number below every stack").at(i + 1, 1, &line))?;
+                    }
+                    stacks.truncate(num_stacks);
                     section = Separator
                 }
             }
-            Separator => section = Moves,
-            Moves => {
-                moves.push(line.parse::<Move>()?)
+            Separator => {
+                if !line.is_empty() {
+                    problem(Error::expected("a blank line before the moves").at(i + 1, 1, &line))?;
+                }
+                section = Moves
+            }
+            Moves => match parse_move(i + 1, &line, stacks.len()) {
+                Ok(mov) => moves.push(mov),
+                Err(e) => problem(e)?,
             }
         }
     }
     Ok((stacks, moves))
 }
 
-fn top_crates() -> Result<String, Error> {
-    let (mut stacks, moves) = parse("input-05.txt")?;
-    // println!("{:?}", stacks);
-    // println!("{:?}", moves);
+/// Upper bound on the number of stacks; the stack number line tells how many there really are.
+pub const MAX_STACKS: usize = 9;
+
+pub fn parse(input: impl BufRead) -> Result<(Stacks, Vec<Move>)> {
+    parse_lines(input, MAX_STACKS, &mut Err)
+}
+
+pub fn top_crates(stacks: &Stacks, moves: &[Move]) -> Result<String> {
+    let mut stacks = stacks.clone();
     for mov in moves {
+        event!(5, Trace, "move", crates = mov.num_crates, from = mov.from_stack + 1, to = mov.to_stack + 1);
         make_move(&mut stacks, mov)?;
     }
+    for (i, stack) in stacks.iter().enumerate() {
+        event!(5, Debug, "stack", number = i + 1, crates = stack.iter().collect::<String>());
+    }
     Ok(top_crates_str(&stacks))
 }
 
-fn top_crates_9001() -> Result<String, Error> {
-    let (mut stacks, moves) = parse("input-05.txt")?;
+pub fn top_crates_9001(stacks: &Stacks, moves: &[Move]) -> Result<String> {
+    let mut stacks = stacks.clone();
     for mov in moves {
         make_move_9001(&mut stacks, mov)?;
     }
     Ok(top_crates_str(&stacks))
 }
 
+/// Drawings of more than `max_stacks` stacks are rejected.
+pub struct Day05 {
+    pub max_stacks: usize,
+}
+
+impl Default for Day05 {
+    fn default() -> Self {
+        Day05 { max_stacks: MAX_STACKS }
+    }
+}
+
+impl Solution for Day05 {
+    type Input = (Stacks, Vec<Move>);
+
+    params!(max_stacks);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_lines(input.as_bytes(), self.max_stacks, &mut Err)
+    }
+
+    fn part1(&self, (stacks, moves): &Self::Input) -> Result<Answer> {
+        top_crates(stacks, moves).map(Answer::from)
+    }
+
+    fn part2(&self, (stacks, moves): &Self::Input) -> Result<Answer> {
+        top_crates_9001(stacks, moves).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        collect_problems(|problem| parse_lines(input.as_bytes(), self.max_stacks, problem))
+    }
+}
+
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_top_crates() {
-        println!("{}", top_crates().unwrap());
+        let (stacks, moves) = parse(open_input("input-05.txt").unwrap()).unwrap();
+        println!("{}", top_crates(&stacks, &moves).unwrap());
     }
 
     #[test]
     fn print_top_crates_9001() {
-        println!("{}", top_crates_9001().unwrap());
+        let (stacks, moves) = parse(open_input("input-05.txt").unwrap()).unwrap();
+        println!("{}", top_crates_9001(&stacks, &moves).unwrap());
     }
 }
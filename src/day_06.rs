@@ -1,46 +1,83 @@
+//! Day 6: Tuning Trouble.
+
 use std::collections::HashSet;
-use std::fs::read_to_string;
-use std::io;
 
-use Error::*;
+use crate::check::check_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    MarkerNotFound,
+// the datastream is a single line of letters
+fn check_stream(line_no: usize, line: &str) -> Result<()> {
+    if line_no > 1 {
+        return Err(Error::expected("a single line").at(line_no, 1, line));
+    }
+    match line.chars().position(|c| !c.is_ascii_lowercase()) {
+        Some(col) => Err(Error::expected("a letter `a`-`z`").at(line_no, col + 1, line)),
+        None => Ok(()),
+    }
 }
 
-fn prefix_length(marker_length: usize) -> Result<usize, Error> {
-    let stream = read_to_string("input-06.txt").map_err(|e| IO(e))?;
-    for i in marker_length..stream.len() {
+pub fn parse(input: &str) -> Result<String> {
+    for (i, line) in input.lines().enumerate() {
+        check_stream(i + 1, line)?;
+    }
+    Ok(input.trim_end().to_string())
+}
+
+pub fn prefix_length(stream: &str, marker_length: usize) -> Result<usize> {
+    for i in marker_length..=stream.len() {
         let set: HashSet<char> = HashSet::from_iter(stream[i - marker_length..i].chars());
         if set.len() == marker_length {
             return Ok(i);
         }
     }
-    Err(MarkerNotFound)
+    Err(Error::no_answer(format!("no marker of {marker_length} distinct characters found")))
+}
+
+pub fn packet_prefix_length(stream: &str) -> Result<usize> {
+    prefix_length(stream, 4)
 }
 
-fn packet_prefix_length() -> Result<usize, Error> {
-    prefix_length(4)
+pub fn message_prefix_length(stream: &str) -> Result<usize> {
+    prefix_length(stream, 14)
 }
 
-fn message_prefix_length() -> Result<usize, Error> {
-    prefix_length(14)
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(&self, stream: &Self::Input) -> Result<Answer> {
+        packet_prefix_length(stream).map(Answer::from)
+    }
+
+    fn part2(&self, stream: &Self::Input) -> Result<Answer> {
+        message_prefix_length(stream).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, check_stream)
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use std::fs::read_to_string;
+
     use super::*;
 
     #[test]
     fn print_packet_prefix_length() {
-        println!("{}", packet_prefix_length().unwrap());
+        println!("{}", packet_prefix_length(&read_to_string("input-06.txt").unwrap()).unwrap());
     }
 
     #[test]
     fn print_message_prefix_length() {
-        println!("{}", message_prefix_length().unwrap());
+        println!("{}", message_prefix_length(&read_to_string("input-06.txt").unwrap()).unwrap());
     }
 }
 
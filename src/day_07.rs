@@ -1,48 +1,46 @@
+//! Day 7: No Space Left On Device.
+
 use std::collections::HashMap;
-use std::fs::File;
-use std::io;
 use std::io::BufRead;
-use std::num::ParseIntError;
 
 use Command::*;
-use Error::*;
-
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    EmptyCommand,
-    UnrecognisedCommand(String),
-    MissingArg(String),
-    EmptyLine,
-    ParseInt(ParseIntError),
-}
+
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::solution::{params, Answer, Solution};
+use crate::trace::{self, event, Level};
+
+// total size of each directory, keyed by its path
+pub type DirMap = HashMap<Vec<String>, u64>;
 
 enum Command<'a> {
     Ls,
     Cd(&'a str),
 }
 
-fn parse_command(line: &String) -> Result<Command, Error> {
+fn parse_command(line_no: usize, line: &str) -> Result<Command<'_>> {
     let mut parts = line.split_whitespace();
     parts.next();
-    match parts.next().ok_or(EmptyCommand)? {
-        "cd" => parts.next().map(|dir| Cd(dir)).ok_or(MissingArg(line.to_string())),
+    let command = parts.next()
+        .ok_or_else(|| Error::expected("a command after `$`").at(line_no, line.len() + 1, line))?;
+    match command {
+        "cd" => parts.next().map(Cd)
+            .ok_or_else(|| Error::expected("a directory name").at(line_no, line.len() + 1, line)),
         "ls" => Ok(Ls),
-        other => Err(UnrecognisedCommand(other.to_string()))
+        other => Err(Error::expected("`cd` or `ls`").at_token(line_no, line, other))
     }
 }
 
-fn parse_ls_entry_size(line: &String) -> Result<u64, Error> {
+fn parse_ls_entry_size(line_no: usize, line: &str) -> Result<u64> {
     let mut parts = line.split_whitespace();
-    match parts.next().ok_or(EmptyLine)? {
+    match parts.next().ok_or_else(|| Error::expected("a directory listing entry").at(line_no, 1, line))? {
         "dir" => Ok(0),
-        num => num.parse::<u64>().map_err(|e| ParseInt(e))
+        num => num.parse::<u64>().at_token(line_no, line, num)
     }
 }
 
-fn parse(path: &str) -> Result<HashMap<Vec<String>, u64>, Error> {
+pub fn parse(input: impl BufRead) -> Result<DirMap> {
     let mut result = HashMap::new();
-    let file = File::open(path).map_err(|e| IO(e))?;
     let mut cwd: Vec<String> = Vec::new();
     let mut cwd_size: u64 = 0;
     let mut finish_ls = |mut cwd: Vec<String>, cwd_size: u64| {
@@ -55,54 +53,124 @@ fn parse(path: &str) -> Result<HashMap<Vec<String>, u64>, Error> {
             }
         }
     };
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        if line.starts_with("$") {
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.starts_with('$') {
             finish_ls(cwd.clone(), cwd_size);
             cwd_size = 0;
-            match parse_command(&line)? {
+            match parse_command(i + 1, &line)? {
                 Ls => (),
                 Cd("/") => cwd.clear(),
                 Cd("..") => {
                     cwd.pop();
-                    ()
                 }
                 Cd(dir) => cwd.push(String::from(dir))
             }
         } else {
-            cwd_size += parse_ls_entry_size(&line)?;
+            cwd_size += parse_ls_entry_size(i + 1, &line)?;
         }
     }
     finish_ls(cwd.clone(), cwd_size);
+    if trace::enabled(7, Level::Debug) {
+        let mut dirs = result.iter().collect::<Vec<_>>();
+        dirs.sort();
+        for (path, size) in dirs {
+            event!(7, Debug, "directory", path = path_name(path), size = size);
+        }
+    }
     Ok(result)
 }
 
-fn small_dirs_size() -> Result<u64, Error> {
-    let dir_map = parse("input-07.txt")?;
-    //println!("{:?}", dir_map);
-    Ok(dir_map.values().filter(|v| v <= &&100_000).sum())
+pub const SMALL_DIR_SIZE: u64 = 100_000;
+pub const DISK_SIZE: u64 = 70_000_000;
+pub const REQUIRED_SPACE: u64 = 30_000_000;
+
+pub fn small_dirs_size(dir_map: &DirMap, max_size: u64) -> u64 {
+    dir_map.values().filter(|&&v| v <= max_size).sum()
+}
+
+pub fn space_to_free(dir_map: &DirMap, disk_size: u64, required_space: u64) -> Result<u64> {
+    dir_to_free(dir_map, disk_size, required_space).map(|(_, size)| size)
+}
+
+/// The smallest directory whose deletion leaves `required_space` free, with its size.
+pub fn dir_to_free(dir_map: &DirMap, disk_size: u64, required_space: u64) -> Result<(&[String], u64)> {
+    let used_space = dir_map.get(&vec![]).copied().unwrap_or(0);
+    let free_space = disk_size.checked_sub(used_space)
+        .ok_or_else(|| Error::no_answer("the files do not fit on the disk"))?;
+    let to_free = required_space.saturating_sub(free_space);
+    dir_map.iter()
+        .filter(|&(_, &size)| size >= to_free)
+        .min_by_key(|&(path, &size)| (size, path))
+        .map(|(path, &size)| (path.as_slice(), size))
+        .ok_or_else(|| Error::no_answer("no directory is big enough to free the required space"))
+}
+
+fn path_name(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+/// Part 1 sums the directories of at most `small_dir_size`; part 2 makes `required_space`
+/// free on a disk of `disk_size`.
+pub struct Day07 {
+    pub small_dir_size: u64,
+    pub disk_size: u64,
+    pub required_space: u64,
 }
 
-fn space_to_free() -> Result<u64, Error> {
-    let dir_map = parse("input-07.txt")?;
-    const DISK_SIZE: u64 = 70_000_000;
-    const REQUIRED_SPACE: u64 = 30_000_000;
-    let free_space = DISK_SIZE - dir_map[&vec![]];
-    let to_free = REQUIRED_SPACE - free_space;
-    Ok(dir_map.values().filter(|v| v >= &&to_free).min().unwrap().to_owned())
+impl Default for Day07 {
+    fn default() -> Self {
+        Day07 { small_dir_size: SMALL_DIR_SIZE, disk_size: DISK_SIZE, required_space: REQUIRED_SPACE }
+    }
+}
+
+impl Solution for Day07 {
+    type Input = DirMap;
+
+    params!(small_dir_size, disk_size, required_space);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, dir_map: &Self::Input) -> Result<Answer> {
+        Ok(small_dirs_size(dir_map, self.small_dir_size).into())
+    }
+
+    fn part2(&self, dir_map: &Self::Input) -> Result<Answer> {
+        space_to_free(dir_map, self.disk_size, self.required_space).map(Answer::from)
+    }
+
+    fn explain2(&self, dir_map: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let (path, size) = dir_to_free(dir_map, self.disk_size, self.required_space)?;
+        Ok((size.into(), Some(format!("delete {} of size {size}", path_name(path)))))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        // every line is a command or a listing entry on its own
+        check_lines(input, |i, line| {
+            if line.starts_with('$') {
+                parse_command(i, line).map(drop)
+            } else {
+                parse_ls_entry_size(i, line).map(drop)
+            }
+        })
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_small_dirs_size() {
-        println!("{}", small_dirs_size().unwrap());
+        println!("{}", small_dirs_size(&parse(open_input("input-07.txt").unwrap()).unwrap(), SMALL_DIR_SIZE));
     }
 
     #[test]
     fn print_space_to_free() {
-        println!("{}", space_to_free().unwrap());
+        println!("{}", space_to_free(&parse(open_input("input-07.txt").unwrap()).unwrap(), DISK_SIZE, REQUIRED_SPACE).unwrap());
     }
 }
@@ -1,170 +1,122 @@
-use std::fs::File;
-use std::io;
+//! Day 8: Treetop Tree House.
+
 use std::io::BufRead;
 
-use Error::*;
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::point::{Dir, Point};
+use crate::solution::{Answer, Solution};
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error)
+fn height(c: char) -> Option<u8> {
+    c.to_digit(10).map(|h| h as u8)
 }
 
-fn parse(line: &str) -> Result<Vec<u8>, Error> {
-    let mut result: Vec<u8> = Vec::new();
-    for c in line.chars() {
-        result.push(c as u8 - '0' as u8)
-    }
-    Ok(result)
+pub fn read(input: impl BufRead) -> Result<Grid<u8>> {
+    Grid::parse(input, height, "a digit")
 }
 
-fn read(path: &str) -> Result<Vec<Vec<u8>>, Error> {
-    let mut result: Vec<Vec<u8>> = Vec::new();
-    let file = File::open(path).map_err(|e| IO(e))?;
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        result.push(parse(&line)?)
-    }
-    Ok(result)
+pub fn tree_cover(trees: &Grid<u8>) -> usize {
+    visibility(trees).iter().filter(|&(_, &v)| v).count()
 }
 
-fn tree_cover() -> Result<usize, Error> {
-    let trees = read("input-08.txt")?;
-    let width = trees[0].len();
-    let height = trees.len();
-    let mut mask: Vec<Vec<bool>> = vec![vec![false; width]; height];
-
-    // scans
-    //  top-down
-    let mut row_max: Vec<u8> = vec![0; width];
-    for row in 0..height {
-        for col in 0..width {
-            if trees[col][row] > row_max[col] { // TODO: indices are swapped here?
-                row_max[col] = trees[col][row];
-                mask[col][row] = true
-            }
-        }
+/// Which trees are visible from outside the grid.
+pub fn visibility(trees: &Grid<u8>) -> Grid<bool> {
+    // scan every row and column from both ends: a tree is visible if it is higher than all before it
+    let mut visible = trees.map(|_| false);
+    let rows = trees.y_range().map(|y| trees.x_range().map(|x| Point::new(x, y)).collect::<Vec<_>>());
+    let columns = trees.x_range().map(|x| trees.y_range().map(|y| Point::new(x, y)).collect::<Vec<_>>());
+    for line in rows.chain(columns) {
+        mark_visible(trees, &mut visible, line.iter());
+        mark_visible(trees, &mut visible, line.iter().rev());
     }
-    //  bottom-up
-    let mut row_max: Vec<u8> = vec![0; width];
-    for row in (0..height).rev() {
-        for col in 0..width {
-            if trees[col][row] > row_max[col] {
-                row_max[col] = trees[col][row];
-                mask[col][row] = true
-            }
-        }
-    }
-    //  left-right
-    let mut col_max: Vec<u8> = vec![0; height];
-    for col in 0..width {
-        for row in 0..height {
-            if trees[col][row] > col_max[row] {
-                col_max[row] = trees[col][row];
-                mask[col][row] = true
-            }
-        }
-    }
-    //  right-left
-    let mut col_max: Vec<u8> = vec![0; height];
-    for col in (0..width).rev() {
-        for row in 0..height {
-            if trees[col][row] > col_max[row] {
-                col_max[row] = trees[col][row];
-                mask[col][row] = true
-            }
+    visible
+}
+
+fn mark_visible<'a>(trees: &Grid<u8>, visible: &mut Grid<bool>, line: impl Iterator<Item = &'a Point>) {
+    let mut highest = None;
+    for &pos in line {
+        if highest.is_none_or(|h| trees[pos] > h) {
+            visible[pos] = true;
+            highest = Some(trees[pos]);
         }
     }
+}
 
-    // for row in 0..height {
-    //     for col in 0..width {
-    //         let c = if mask[col][row] { "X" } else { "." };
-    //         print!("{}", c);
-    //     }
-    //     println!();
-    // }
-
-    let num_visible = mask.iter().map(|row|
-        row.iter().filter(|v| v == &&true).count()
-    ).sum();
-    Ok(num_visible)
+pub fn scenic_score(trees: &Grid<u8>) -> u64 {
+    best_tree(trees).map_or(0, |(_, score)| score)
 }
 
-fn scenic_score() -> Result<u64, Error> {
-    let trees = read("input-08.txt")?;
-    let width = trees[0].len();
-    let height = trees.len();
-
-    let mut view_dists: Vec<Vec<Vec<u64>>> = vec![vec![vec![0; 4]; width]; height];
-
-    // TODO: scan in each dir, keeping track of the coord of most recent tree >= x for x in 0..9
-
-    //  top-down
-    let mut dir = 0;
-    let mut height_coords: Vec<Vec<usize>> = vec![vec![0; 10]; width];
-    for row in 0..height {
-        for col in 0..width {
-            let tree_height = trees[row][col] as usize;
-            view_dists[row][col][dir] = row.abs_diff(height_coords[col][tree_height]) as u64;
-            for h in 0..=tree_height {
-                height_coords[col][h] = row
-            }
-        }
+/// The tree with the highest scenic score, the first in reading order if several share it.
+pub fn best_tree(trees: &Grid<u8>) -> Option<(Point, u64)> {
+    let scores = scenic_scores(trees);
+    let best = scores.iter().map(|(_, &score)| score).max()?;
+    let pos = scores.iter().find(|&(_, &score)| score == best).map(|(pos, _)| pos)?;
+    Some((pos, best))
+}
+
+/// The scenic score of every tree.
+pub fn scenic_scores(trees: &Grid<u8>) -> Grid<u64> {
+    let mut scores = trees.map(|_| 0);
+    for (pos, height) in trees.iter() {
+        scores[pos] = Dir::ALL.iter()
+            .map(|dir| {
+                // the view stops at the first tree at least as high, or at the edge
+                let mut view_dist = 0;
+                for (_, h) in trees.ray(pos, dir.offset()) {
+                    view_dist += 1;
+                    if h >= height {
+                        break;
+                    }
+                }
+                view_dist
+            })
+            .product::<u64>();
     }
-    //  bottom-up
-    dir += 1;
-    let mut height_coords: Vec<Vec<usize>> = vec![vec![height-1; 10]; width];
-    for row in (0..height).rev() {
-        for col in 0..width {
-            let tree_height = trees[row][col] as usize;
-            view_dists[row][col][dir] = row.abs_diff(height_coords[col][tree_height]) as u64;
-            for h in 0..=tree_height {
-                height_coords[col][h] = row
-            }
-        }
+    scores
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Grid<u8>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        read(input.as_bytes())
     }
-    //  left-right
-    dir += 1;
-    let mut height_coords: Vec<Vec<usize>> = vec![vec![0; 10]; height];
-    for col in 0..width {
-        for row in 0..height {
-            let tree_height = trees[row][col] as usize;
-            view_dists[row][col][dir] = col.abs_diff(height_coords[row][tree_height]) as u64;
-            for h in 0..=tree_height {
-                height_coords[row][h] = col
-            }
-        }
+
+    fn part1(&self, trees: &Self::Input) -> Result<Answer> {
+        Ok(tree_cover(trees).into())
     }
-    //  right-left
-    dir += 1;
-    let mut height_coords: Vec<Vec<usize>> = vec![vec![width-1; 10]; height];
-    for col in (0..width).rev() {
-        for row in 0..height {
-            let tree_height = trees[row][col] as usize;
-            view_dists[row][col][dir] = col.abs_diff(height_coords[row][tree_height]) as u64;
-            for h in 0..=tree_height {
-                height_coords[row][h] = col
-            }
-        }
+
+    fn part2(&self, trees: &Self::Input) -> Result<Answer> {
+        Ok(scenic_score(trees).into())
     }
 
+    fn explain2(&self, trees: &Self::Input) -> Result<(Answer, Option<String>)> {
+        Ok(match best_tree(trees) {
+            Some((pos, score)) => (score.into(), Some(format!("the tree at {pos}, of height {}", trees[pos]))),
+            None => (0u64.into(), None),
+        })
+    }
 
-    let best_score = view_dists.iter().flat_map(|row|
-        row.iter().map(|dists| dists.iter().product::<u64>()).max()
-    ).max().unwrap();
-    Ok(best_score)
+    fn check(&self, input: &str) -> Vec<Error> {
+        Grid::check(input, height, "a digit")
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_tree_cover() {
-        println!("{}", tree_cover().unwrap());
+        println!("{}", tree_cover(&read(open_input("input-08.txt").unwrap()).unwrap()));
     }
 
     #[test]
     fn print_scenic_score() {
-        println!("{}", scenic_score().unwrap());
+        println!("{}", scenic_score(&read(open_input("input-08.txt").unwrap()).unwrap()));
     }
 }
\ No newline at end of file
@@ -1,121 +1,121 @@
+//! Day 9: Rope Bridge.
+
 use std::collections::HashSet;
-use std::fs::File;
-use std::io;
 use std::io::BufRead;
-use std::num::ParseIntError;
-use std::str::FromStr;
-
-use Dir::*;
-use Error::*;
-
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    NoToken(String),
-    ParseInt(ParseIntError),
-    UnrecognisedDir(String),
-}
 
-#[derive(Debug, Copy, Clone)]
-enum Dir {
-    Left,
-    Right,
-    Up,
-    Down,
-}
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::point::{Dir, Point};
+use crate::solution::{Answer, Solution};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Pos {
-    pub x: i32,
-    pub y: i32,
+fn parse_dir(s: &str) -> Result<Dir> {
+    match s {
+        "L" => Ok(Dir::Left),
+        "R" => Ok(Dir::Right),
+        "U" => Ok(Dir::Up),
+        "D" => Ok(Dir::Down),
+        _ => Err(Error::expected("`L`, `R`, `U` or `D`"))
+    }
 }
 
-impl Pos {
-    fn adjacent_to(self: &Pos, pos2: &Pos) -> bool {
-        self.x.abs_diff(pos2.x) <= 1 &&
-            self.y.abs_diff(pos2.y) <= 1
+fn move_tail(head_pos: Point, tail_pos: Point) -> Point {
+    if head_pos.touches(tail_pos) {
+        tail_pos
+    } else {
+        tail_pos + (head_pos - tail_pos).signum()
     }
 }
 
-impl FromStr for Dir {
-    type Err = Error;
+pub type Motion = (Dir, u32);
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Left),
-            "R" => Ok(Right),
-            "U" => Ok(Up),
-            "D" => Ok(Down),
-            _ => Err(UnrecognisedDir(s.to_string()))
-        }
-    }
+fn parse_motion(line_no: usize, line: &str) -> Result<Motion> {
+    let no_token = || Error::expected("a direction and a step count").at(line_no, 1, line);
+    let mut parts = line.split_whitespace();
+    let dir = parts.next().ok_or_else(no_token)?;
+    let steps = parts.next().ok_or_else(no_token)?;
+    Ok((
+        parse_dir(dir).at_token(line_no, line, dir)?,
+        steps.parse::<u32>().at_token(line_no, line, steps)?,
+    ))
 }
 
-fn move_head(pos: &Pos, dir: &Dir) -> Pos {
-    match dir {
-        Left => Pos { x: pos.x - 1, y: pos.y },
-        Right => Pos { x: pos.x + 1, y: pos.y },
-        Up => Pos { x: pos.x, y: pos.y + 1 },
-        Down => Pos { x: pos.x, y: pos.y - 1 }
+pub fn parse(input: impl BufRead) -> Result<Vec<Motion>> {
+    let mut motions = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        motions.push(parse_motion(i + 1, &line?)?);
     }
+    Ok(motions)
 }
 
-fn move_tail(head_pos: &Pos, tail_pos: &Pos) -> Pos {
-    if head_pos.adjacent_to(tail_pos) {
-        tail_pos.clone()
-    } else {
-        Pos {
-            x: tail_pos.x + (head_pos.x - tail_pos.x).signum(),
-            y: tail_pos.y + (head_pos.y - tail_pos.y).signum(),
-        }
-    }
+pub fn tail_locs(motions: &[Motion], rope_length: usize) -> usize {
+    tail_visits(motions, rope_length).len()
 }
 
-fn tail_locs(rope_length: usize) -> Result<usize, Error> {
-    let file = File::open("input-09.txt").map_err(|e| IO(e))?;
-    let head: usize = 0;
+/// The positions visited by the tail of a rope with `rope_length` knots, starting at the origin.
+pub fn tail_visits(motions: &[Motion], rope_length: usize) -> HashSet<Point> {
     let tail: usize = rope_length - 1;
-    let mut rope_pos = vec![Pos { x: 0, y: 0 }; rope_length];
-    let mut tail_locs: HashSet<Pos> = HashSet::from([rope_pos[tail]]);
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        let mut parts = line.split_whitespace();
-        let dir = parts.next()
-            .ok_or(NoToken(line.clone()))?
-            .parse::<Dir>()?;
-        let steps = parts.next()
-            .ok_or(NoToken(line.clone()))?
-            .parse::<u32>().map_err(|e| ParseInt(e))?;
+    let mut rope_pos = vec![Point::default(); rope_length];
+    let mut tail_locs: HashSet<Point> = HashSet::from([rope_pos[tail]]);
+    for &(dir, steps) in motions {
         for _ in 0..steps {
-            rope_pos[head] = move_head(&rope_pos[head], &dir);
-            for i in 1..rope_length {
-                rope_pos[i] = move_tail(&rope_pos[i - 1], &rope_pos[i]);
-            }
+            move_rope(&mut rope_pos, dir);
             tail_locs.insert(rope_pos[tail]);
         }
     }
-    Ok(tail_locs.len())
+    tail_locs
+}
+
+/// Moves the head of the rope, the first knot, one step in `dir`, and the other knots after it.
+pub fn move_rope(rope_pos: &mut [Point], dir: Dir) {
+    rope_pos[0] += dir.offset();
+    for i in 1..rope_pos.len() {
+        rope_pos[i] = move_tail(rope_pos[i - 1], rope_pos[i]);
+    }
 }
 
-fn short_tail_locs() -> Result<usize, Error> {
-    tail_locs(2)
+pub fn short_tail_locs(motions: &[Motion]) -> usize {
+    tail_locs(motions, 2)
 }
 
-fn long_tail_locs() -> Result<usize, Error> {
-    tail_locs(10)
+pub fn long_tail_locs(motions: &[Motion]) -> usize {
+    tail_locs(motions, 10)
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<Motion>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, motions: &Self::Input) -> Result<Answer> {
+        Ok(short_tail_locs(motions).into())
+    }
+
+    fn part2(&self, motions: &Self::Input) -> Result<Answer> {
+        Ok(long_tail_locs(motions).into())
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse_motion(i, line).map(drop))
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_short_tail_locs() {
-        println!("{}", short_tail_locs().unwrap());
+        println!("{}", short_tail_locs(&parse(open_input("input-09.txt").unwrap()).unwrap()));
     }
 
     #[test]
     fn print_long_tail_locs() {
-        println!("{}", long_tail_locs().unwrap());
+        println!("{}", long_tail_locs(&parse(open_input("input-09.txt").unwrap()).unwrap()));
     }
 }
\ No newline at end of file
@@ -1,23 +1,17 @@
-use std::fs::File;
-use std::io;
+//! Day 10: Cathode-Ray Tube.
+
 use std::io::BufRead;
-use std::num::ParseIntError;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-use Error::*;
 use Op::*;
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    EmptyLine,
-    MissingArg,
-    UnrecognisedOp(String),
-    ParseInt(ParseIntError),
-}
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::solution::{params, Answer, Solution};
 
 #[derive(Debug, Copy, Clone)]
-enum Op {
+pub enum Op {
     Noop,
     AddX(i64),
 }
@@ -25,25 +19,32 @@ enum Op {
 impl FromStr for Op {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         let mut parts = s.split_whitespace();
-        match parts.next().ok_or(EmptyLine)? {
-            "noop" => Ok(Noop),
-            "addx" => parts.next()
-                .ok_or(MissingArg)
-                .and_then(|v| v.parse::<i64>().map_err(|e| ParseInt(e)))
-                .map(|i| AddX(i)),
-            other => Err(UnrecognisedOp(other.to_string()))
+        match parts.next() {
+            Some("noop") => Ok(Noop),
+            Some("addx") => parts.next()
+                .ok_or_else(|| Error::expected("an argument to `addx`"))
+                .and_then(|v| v.parse::<i64>().map_err(Error::from))
+                .map(AddX),
+            _ => Err(Error::expected("`noop` or `addx V`"))
         }
     }
 }
 
-fn x_vals() -> Result<Vec<i64>, Error> {
+pub fn parse(input: impl BufRead) -> Result<Vec<Op>> {
+    let mut ops = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        ops.push(line.parse::<Op>().at_line(i + 1, &line)?);
+    }
+    Ok(ops)
+}
+
+pub fn x_vals(ops: &[Op]) -> Vec<i64> {
     let mut result: Vec<i64> = vec![];
     let mut x: i64 = 1;
-    let file = File::open("input-10.txt").map_err(|e| IO(e))?;
-    for line in io::BufReader::new(file).lines() {
-        let op = line.map_err(|e| IO(e))?.parse::<Op>()?;
+    for &op in ops {
         let cycles =  match op {
             Noop => 1,
             AddX(_) => 2
@@ -55,46 +56,112 @@ fn x_vals() -> Result<Vec<i64>, Error> {
             x += i;
         }
     }
-    Ok(result)
+    result
 }
 
-fn cycle_x_prod() -> Result<i64, Error> {
-    let control_cycles = [20, 60, 100, 140, 180, 220];
-    let xs = x_vals()?;
+pub const FIRST_CYCLE: usize = 20;
+pub const CYCLE_STEP: usize = 40;
+pub const NUM_CYCLES: usize = 6;
+pub const SCREEN_WIDTH: usize = 40;
+pub const SCREEN_HEIGHT: usize = 6;
+
+/// Sum of the signal strengths during the given cycles, counted from 1.
+pub fn cycle_x_prod(ops: &[Op], control_cycles: &[usize]) -> Result<i64> {
+    let xs = x_vals(ops);
+    if control_cycles.contains(&0) {
+        return Err(Error::no_answer("cycles are counted from 1"));
+    }
+    let last = control_cycles.iter().max().copied().unwrap_or(0);
+    if xs.len() < last {
+        return Err(Error::no_answer(format!("the program runs for {} cycles, fewer than {last}", xs.len())));
+    }
     let mut result: i64 = 0;
-    for cycle in control_cycles {
+    for &cycle in control_cycles {
         result += (cycle as i64) * xs[cycle-1];
     }
     Ok(result)
 }
 
-fn drawing() -> Result<String, Error> {
+pub fn drawing(ops: &[Op], width: usize, height: usize) -> String {
     let mut result: String = String::new();
-    let xs = x_vals()?;
-    for i in 0..240 {
-        if i % 40 == 0 {
+    let xs = x_vals(ops);
+    for (i, x) in xs.iter().enumerate().take(width * height) {
+        if i % width == 0 {
             result.push('\n');
         }
-        if (i as i64 % 40).abs_diff(xs[i]) <= 1 {
+        if ((i % width) as i64).abs_diff(*x) <= 1 {
             result.push('#')
         } else {
             result.push(' ')
         }
     }
-    Ok(result)
+    result
+}
+
+/// The signal strength is measured during `num_cycles` cycles, `cycle_step` apart from
+/// `first_cycle` on, and the screen has `screen_width` x `screen_height` pixels.
+pub struct Day10 {
+    pub first_cycle: usize,
+    pub cycle_step: NonZeroUsize,
+    pub num_cycles: usize,
+    pub screen_width: NonZeroUsize,
+    pub screen_height: NonZeroUsize,
+}
+
+impl Default for Day10 {
+    fn default() -> Self {
+        Day10 {
+            first_cycle: FIRST_CYCLE,
+            cycle_step: NonZeroUsize::new(CYCLE_STEP).unwrap(),
+            num_cycles: NUM_CYCLES,
+            screen_width: NonZeroUsize::new(SCREEN_WIDTH).unwrap(),
+            screen_height: NonZeroUsize::new(SCREEN_HEIGHT).unwrap(),
+        }
+    }
+}
+
+impl Day10 {
+    /// The cycles during which the signal strength is measured.
+    pub fn control_cycles(&self) -> Vec<usize> {
+        (0..self.num_cycles).map(|i| self.first_cycle + i * self.cycle_step.get()).collect()
+    }
+}
+
+impl Solution for Day10 {
+    type Input = Vec<Op>;
+
+    params!(first_cycle, cycle_step, num_cycles, screen_width, screen_height);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, ops: &Self::Input) -> Result<Answer> {
+        cycle_x_prod(ops, &self.control_cycles()).map(Answer::from)
+    }
+
+    fn part2(&self, ops: &Self::Input) -> Result<Answer> {
+        Ok(drawing(ops, self.screen_width.get(), self.screen_height.get()).into())
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| line.parse::<Op>().map(drop).at_line(i, line))
+    }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_cycle_x_prod() {
-        println!("{}", cycle_x_prod().unwrap());
+        println!("{}", cycle_x_prod(&parse(open_input("input-10.txt").unwrap()).unwrap(), &Day10::default().control_cycles()).unwrap());
     }
 
     #[test]
     fn print_drawing() {
-        println!("{}", drawing().unwrap());
+        println!("{}", drawing(&parse(open_input("input-10.txt").unwrap()).unwrap(), SCREEN_WIDTH, SCREEN_HEIGHT));
     }
 }
\ No newline at end of file
@@ -1,48 +1,51 @@
+//! Day 11: Monkey in the Middle.
+
 use std::fmt::Debug;
-use std::fs::File;
-use std::io;
 use std::io::BufRead;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
-use num::bigint::BigUint;
-use num::traits::Zero;
 use regex::Regex;
 
-use Error::*;
-
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    InvalidState(u8),
-    ParseInt(ParseIntError),
-    MalformedStartingItems(String),
-    MalformedOperation(String),
-    UnsupportedOperation(String),
-    MalformedDiv(String),
-    MalformedDest(String),
-}
+use crate::check::{collect_problems, Problem};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::trace::event;
 
-struct Monkey {
-    // TODO: BigUints are super-slow, instead we can track the remainders for 1..20 for each item
-    items: Vec<BigUint>,
-    op: Box<dyn Fn(BigUint) -> BigUint>,
-    dest: Box<dyn Fn(BigUint) -> usize>,
-    inspection_count: u64,
+#[derive(Clone)]
+pub struct Monkey {
+    pub items: Vec<u64>,
+    pub operation: Operation,
+    pub operand: Operand,
+    pub div: u32,
+    pub true_dest: usize,
+    pub false_dest: usize,
+    pub inspection_count: u64,
 }
 
 impl Monkey {
-    fn new(
-        items: Vec<BigUint>,
-        op: Box<dyn Fn(BigUint) -> BigUint>,
-        dest: Box<dyn Fn(BigUint) -> usize>,
-    ) -> Self {
-        Self { items, op, dest, inspection_count: 0 }
+    fn op(&self, wl: u64) -> u128 {
+        let wl = wl as u128;
+        let o = match self.operand {
+            Operand::Num(x) => x as u128,
+            Operand::Old => wl
+        };
+        match self.operation {
+            Operation::Add => wl + o,
+            Operation::Mul => wl * o
+        }
+    }
+
+    fn dest(&self, wl: u64) -> usize {
+        if wl.is_multiple_of(self.div as u64) {
+            self.true_dest
+        } else {
+            self.false_dest
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Operation {
+pub enum Operation {
     Add,
     Mul
 }
@@ -50,17 +53,17 @@ enum Operation {
 impl FromStr for Operation {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "+" => Ok(Operation::Add),
             "*" => Ok(Operation::Mul),
-            other => Err(UnsupportedOperation(other.to_string()))
+            _ => Err(Error::expected("`+` or `*`"))
         }
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Operand {
+pub enum Operand {
     Num(u32),
     Old
 }
@@ -68,155 +71,146 @@ enum Operand {
 impl FromStr for Operand {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         if s == "old" {
             Ok(Operand::Old)
         } else {
-            let x = s.parse::<u32>().map_err(ParseInt)?;
+            let x = s.parse::<u32>()?;
             Ok(Operand::Num(x))
         }
     }
 }
 
-#[derive(Debug, Clone)]
-struct ParserState {
-    state: u8,
-    items: Vec<BigUint>,
-    operation: Operation,
-    operand: Operand,
-    div: u32,
-    true_dest: usize,
-    false_dest: usize,
-}
-
-impl ParserState {
-    pub fn new() -> Self {
-        ParserState {
-            state: 0,
-            items: vec![],
-            operation: Operation::Add,
-            operand: Operand::Old,
-            div: 0,
-            true_dest: 0,
-            false_dest: 0,
-        }
+fn parse_header(line: &str, n: usize) -> Result<()> {
+    if line.trim() == format!("Monkey {n}:") {
+        Ok(())
+    } else {
+        Err(Error::expected(format!("`Monkey {n}:`")))
     }
+}
 
-    pub fn next_line(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        let result = match self.state {
-            0 | 6 => Ok(None), // skip
-            1 => self.parse_items(line),
-            2 => self.parse_op(line),
-            3 => self.parse_div(line),
-            4 => self.parse_true_dest(line),
-            5 => self.parse_false_dest(line),
-            other => Err(InvalidState(other))
-        };
-        self.state = (self.state + 1) % 7;
-        result
+fn parse_items(line: &str) -> Result<Vec<u64>> {
+    let re = Regex::new(r"^\s*Starting items:(.*)$").unwrap();
+    let cap = re.captures(line).ok_or_else(|| Error::expected("`Starting items: A, B, ...`"))?;
+    let mut vals = vec![];
+    for s in cap[1].split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        vals.push(s.parse::<u64>()?)
     }
+    Ok(vals)
+}
 
-    fn parse_items(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        let re = Regex::new(r"\s*Starting items: (((\d+)(,\s*)?)*)").unwrap();
-        let cap = re.captures(line).ok_or(MalformedStartingItems(line.to_string()))?;
-        let mut vals = vec![];
-        for s in cap[1].split(", ") {
-            vals.push(s.parse::<u32>().map_err(ParseInt).map(BigUint::from)?)
-        }
-        self.items = vals;
-        Ok(None)
-    }
+fn parse_op(line: &str) -> Result<(Operation, Operand)> {
+    let re = Regex::new(r"^\s*Operation: new\s*=\s*old\s*(\+|\*)\s*(old|\d+)\s*$").unwrap();
+    let cap = re.captures(line).ok_or_else(|| Error::expected("`Operation: new = old OP X`"))?;
+    Ok((cap[1].parse::<Operation>()?, cap[2].parse::<Operand>()?))
+}
 
-    fn parse_op(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        let re = Regex::new(r"\s*Operation: new\s*=\s*old\s*(\+|\*)\s*(old|\d+)").unwrap();
-        let cap = re.captures(line).ok_or(MalformedOperation(line.to_string()))?;
-        let operation = cap[1].parse::<Operation>()?;
-        let operand = cap[2].parse::<Operand>()?;
-        self.operation = operation;
-        self.operand = operand;
-        Ok(None)
+fn parse_div(line: &str) -> Result<u32> {
+    let re = Regex::new(r"^\s*Test: divisible by (\d+)\s*$").unwrap();
+    let cap = re.captures(line).ok_or_else(|| Error::expected("`Test: divisible by N`"))?;
+    match cap[1].parse::<u32>()? {
+        0 => Err(Error::expected("a non-zero divisor")),
+        div => Ok(div),
     }
+}
 
-    fn parse_div(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        let re = Regex::new(r"\s*Test: divisible by (\d+)").unwrap();
-        let cap = re.captures(line).ok_or(MalformedDiv(line.to_string()))?;
-        self.div = cap[1].parse::<u32>().map_err(ParseInt)?;
-        Ok(None)
-    }
+fn parse_dest(line: &str, branch: &str) -> Result<usize> {
+    let re = Regex::new(format!(r"^\s*If {}: throw to monkey (\d+)\s*$", branch).as_str()).unwrap();
+    let cap = re.captures(line)
+        .ok_or_else(|| Error::expected(format!("`If {branch}: throw to monkey N`")))?;
+    Ok(cap[1].parse::<usize>()?)
+}
 
-    fn parse_true_dest(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        self.true_dest = self.parse_dest(line, "true")?;
-        Ok(None)
-    }
+const MONKEY_LINES: usize = 6;
 
-    fn parse_false_dest(&mut self, line: &String) -> Result<Option<Monkey>, Error> {
-        self.false_dest = self.parse_dest(line, "false")?;
-        Ok(Some(self.monkey()))
+// `None` if the line is malformed, after reporting it to `problem`
+fn reported<T>(result: Result<T>, (line_no, line): &(usize, String), problem: &mut Problem) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => problem(e.at(*line_no, 1, line)).map(|_| None),
     }
+}
 
-    fn parse_dest(&mut self, line: &String, branch: &str) -> Result<usize, Error> {
-        let re = Regex::new(format!(r"\s*If {}: throw to monkey (\d+)", branch).as_str()).unwrap();
-        let cap = re.captures(line).ok_or(MalformedDest(line.to_string()))?;
-        cap[1].parse::<usize>().map_err(ParseInt)
+// Parses the description of monkey `n`. Every malformed line is reported to `problem`,
+// which can stop parsing by returning the error, or carry on.
+fn parse_monkey(n: usize, lines: &[(usize, String)], problem: &mut Problem) -> Result<Option<Monkey>> {
+    if lines.len() != MONKEY_LINES {
+        let (line_no, line) = &lines[0];
+        let e = Error::expected(format!("{MONKEY_LINES} lines describing monkey {n}, then a blank line"));
+        return problem(e.at(*line_no, 1, line)).map(|_| None);
     }
+    let header = reported(parse_header(&lines[0].1, n), &lines[0], problem)?;
+    let items = reported(parse_items(&lines[1].1), &lines[1], problem)?;
+    let op = reported(parse_op(&lines[2].1), &lines[2], problem)?;
+    let div = reported(parse_div(&lines[3].1), &lines[3], problem)?;
+    let true_dest = reported(parse_dest(&lines[4].1, "true"), &lines[4], problem)?;
+    let false_dest = reported(parse_dest(&lines[5].1, "false"), &lines[5], problem)?;
+    match (header, items, op, div, true_dest, false_dest) {
+        (Some(()), Some(items), Some((operation, operand)), Some(div), Some(true_dest), Some(false_dest)) => {
+            Ok(Some(Monkey { items, operation, operand, div, true_dest, false_dest, inspection_count: 0 }))
+        }
+        _ => Ok(None),
+    }
+}
 
-    fn monkey(&self) -> Monkey {
-        let operation = self.operation.to_owned();
-        let operand = self.operand.to_owned();
-        let op = move |wl: BigUint| {
-            let o = match operand {
-                Operand::Num(x) => BigUint::from(x),
-                Operand::Old => wl.clone()
-            };
-            match operation {
-                Operation::Add => wl + o,
-                Operation::Mul => wl * o
-            }
-        };
-        let div = self.div.to_owned();
-        let true_dest = self.true_dest.to_owned();
-        let false_dest = self.false_dest.to_owned();
-        let dest = move |wl: BigUint|
-            if wl % div == Zero::zero() {
-                true_dest
-            } else {
-                false_dest
-            };
-        Monkey::new(
-            self.items.clone(),
-            Box::new(op),
-            Box::new(dest),
-        )
-    }
-}
-
-fn parse(path: &str) -> Result<Vec<Monkey>, Error> {
+// Monkeys are separated by blank lines.
+// Every malformed line is reported to `problem`, as for `parse_monkey`.
+fn parse_lines(input: impl BufRead, problem: &mut Problem) -> Result<Vec<Monkey>> {
+    let mut blocks: Vec<Vec<(usize, String)>> = vec![vec![]];
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            blocks.push(vec![]);
+        } else {
+            blocks.last_mut().unwrap().push((i + 1, line));
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
     let mut result = vec![];
-    let mut state = ParserState::new();
-    let file = File::open(path).map_err(|e| IO(e))?;
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        if let Some(monkey) = state.next_line(&line)? {
+    for (n, block) in blocks.iter().enumerate() {
+        if let Some(monkey) = parse_monkey(n, block, problem)? {
             result.push(monkey);
         }
     }
+    for (n, m) in result.iter().enumerate() {
+        if m.true_dest.max(m.false_dest) >= blocks.len() {
+            problem(Error::expected(format!("monkey {n} to throw to one of the {} monkeys", blocks.len())))?;
+        }
+    }
     Ok(result)
 }
 
-fn monkey_business_level(div: u32, num_rounds: u16) -> Result<u64, Error> {
-    let mut monkeys = parse("input-11.txt")?;
+pub fn parse(input: impl BufRead) -> Result<Vec<Monkey>> {
+    parse_lines(input, &mut Err)
+}
+
+pub fn monkey_business_level(monkeys: &[Monkey], div: u32, num_rounds: u16) -> Result<u64> {
+    if monkeys.len() < 2 {
+        return Err(Error::no_answer("fewer than two monkeys"));
+    }
+    // the tests only look at remainders, so without relief the worry levels can be kept modulo
+    // the product of all divisors; relief divides what is left, so it needs the exact levels
+    let modulus = match div {
+        1 => Some(monkeys.iter()
+            .try_fold(1u64, |acc, m| acc.checked_mul(m.div as u64))
+            .ok_or_else(|| Error::no_answer("the product of the divisors does not fit in 64 bits"))?),
+        _ => None,
+    };
+    let mut monkeys = monkeys.to_vec();
     let num_monkeys = monkeys.len();
-    for _round in 0..num_rounds {
+    for round in 0..num_rounds {
         for m in 0..num_monkeys {
             let monkey = &mut monkeys[m];
-            let mut thrown: Vec<Vec<BigUint>> = vec![vec![]; num_monkeys];
-            for item in &monkey.items {
-                let mut wl = (monkey.op)(item.to_owned());
+            let mut thrown: Vec<Vec<u64>> = vec![vec![]; num_monkeys];
+            for &item in &monkey.items {
+                let wl = monkey.op(item);
+                let wl = modulus.map_or(wl, |modulus| wl % modulus as u128) / div as u128;
+                let wl = u64::try_from(wl)
+                    .map_err(|_| Error::no_answer(format!("worry level {wl} does not fit in 64 bits")))?;
                 monkey.inspection_count += 1;
-                wl /= div;
-                let dest = (monkey.dest)(wl.clone());
+                let dest = monkey.dest(wl);
                 thrown[dest].push(wl);
-                // println!("[{}]: ({} -> {}) => [{}]", m, item, wl, dest);
+                event!(11, Debug, "throw", round = round + 1, monkey = m, item = item, worry = wl, to = dest);
             }
             monkey.items.clear();
             for om in 0..num_monkeys {
@@ -224,26 +218,77 @@ fn monkey_business_level(div: u32, num_rounds: u16) -> Result<u64, Error> {
             }
         }
     }
+    for (m, monkey) in monkeys.iter().enumerate() {
+        event!(11, Info, "inspections", monkey = m, count = monkey.inspection_count);
+    }
     let mut counts: Vec<u64> = monkeys.iter()
         .map(|m| m.inspection_count)
         .collect();
     counts.sort();
     counts.reverse();
-    // println!("{:?}", counts);
     Ok(counts[0] * counts[1])
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, monkeys: &Self::Input) -> Result<Answer> {
+        monkey_business_level(monkeys, 3, 20).map(Answer::from)
+    }
+
+    fn part2(&self, monkeys: &Self::Input) -> Result<Answer> {
+        monkey_business_level(monkeys, 1, 10000).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        collect_problems(|problem| parse_lines(input.as_bytes(), problem))
+    }
+}
+
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_monkey_business_level_div_3() {
-        println!("{}", monkey_business_level(3, 20).unwrap());
+        println!("{}", monkey_business_level(&parse(open_input("input-11.txt").unwrap()).unwrap(), 3, 20).unwrap());
     }
 
     #[test]
     fn print_monkey_business_level_no_div() {
-        println!("{}", monkey_business_level(1, 10000).unwrap());
+        println!("{}", monkey_business_level(&parse(open_input("input-11.txt").unwrap()).unwrap(), 1, 10000).unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn relief_divides_the_exact_worry_levels() {
+        let monkeys = "Monkey 0:
+            Starting items: 64
+            Operation: new = old * 16
+            Test: divisible by 7
+            If true: throw to monkey 2
+            If false: throw to monkey 1
+
+            Monkey 1:
+            Starting items: 56, 78, 98, 99
+            Operation: new = old + 15
+            Test: divisible by 7
+            If true: throw to monkey 0
+            If false: throw to monkey 0
+
+            Monkey 2:
+            Starting items: 3
+            Operation: new = old + old
+            Test: divisible by 17
+            If true: throw to monkey 1
+            If false: throw to monkey 0";
+        assert_eq!(monkey_business_level(&parse(monkeys.as_bytes()).unwrap(), 3, 20).unwrap(), 11845);
+    }
+}
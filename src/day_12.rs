@@ -1,87 +1,113 @@
-use std::collections::{HashSet, VecDeque};
-use std::fs::File;
-use std::io;
+//! Day 12: Hill Climbing Algorithm.
+
+use std::collections::HashSet;
 use std::io::BufRead;
-use Error::*;
+
+use crate::error::{Error, Result};
+use crate::graph::bfs;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::solution::{Answer, Solution};
 
 // directed graph: each node holds a list of previous nodes
-type Node = usize;
-type Graph = Vec<Vec<Node>>;
+pub type Node = usize;
+pub type Graph = Vec<Vec<Node>>;
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    EmptyQueue,
+pub struct Terrain {
+    pub graph: Graph,
+    pub start: Node,
+    /// the best signal
+    pub end: Node,
+    /// whether each node is at the lowest elevation
+    pub floor: Vec<bool>,
+    /// width of the heightmap, to tell the nodes' positions
+    pub width: usize,
 }
 
-fn shortest_start_end() -> Result<u16, Error> {
-    let (graph, start, end, _floor) = parse_graph("input-12.txt")?;
-    let stop_nodes = HashSet::from([start]);
-    shortest_path(&graph, end, &stop_nodes)
+pub fn shortest_start_end(terrain: &Terrain) -> Result<u16> {
+    let stop_nodes = HashSet::from([terrain.start]);
+    shortest_path(&terrain.graph, terrain.end, &stop_nodes)
 }
 
-fn shortest_floor_end() -> Result<u16, Error> {
-    let (graph, _start, end, floor) = parse_graph("input-12.txt")?;
+pub fn shortest_floor_end(terrain: &Terrain) -> Result<u16> {
     let mut stop_nodes = HashSet::new();
-    for i in 0..floor.len() {
-        if floor[i] {
+    for (i, is_floor) in terrain.floor.iter().enumerate() {
+        if *is_floor {
             stop_nodes.insert(i);
         }
     }
-    shortest_path(&graph, end, &stop_nodes)
-}
-
-fn shortest_path(graph: &Graph, end: Node, stop_nodes: &HashSet<Node>) -> Result<u16, Error> {
-    // BFS
-    let mut queue = VecDeque::from([end]);
-    let mut distance = vec![-1; graph.len()];
-    distance[end] = 0;
-    loop {
-        let v1 = queue.pop_front().ok_or(EmptyQueue)?;
-        for i in 0..graph[v1].len() {
-            let v2 = graph[v1][i];
-            if distance[v2] == -1 {
-                distance[v2] = distance[v1] + 1;
-                if stop_nodes.contains(&v2) {
-                    return Ok(distance[v2] as u16)
-                }
-                queue.push_back(v2)
-            }
-        }
+    shortest_path(&terrain.graph, terrain.end, &stop_nodes)
+}
+
+pub fn shortest_path(graph: &Graph, end: Node, stop_nodes: &HashSet<Node>) -> Result<u16> {
+    // search backwards, as the graph holds the previous nodes
+    bfs(graph, end, |node| stop_nodes.contains(node))
+        .goal_distance()
+        .map(|distance| distance as u16)
+        .ok_or_else(no_path)
+}
+
+/// The nodes along a shortest path from the start to the best signal, both included.
+pub fn path_start_end(terrain: &Terrain) -> Result<Vec<Node>> {
+    let start = &terrain.start;
+    let mut path = bfs(&terrain.graph, terrain.end, |node| node == start).path_to(start).ok_or_else(no_path)?;
+    path.reverse();
+    Ok(path)
+}
+
+/// The nodes along a shortest path from any of the lowest squares to the best signal, both included.
+pub fn path_floor_end(terrain: &Terrain) -> Result<Vec<Node>> {
+    let search = bfs(&terrain.graph, terrain.end, |&node| terrain.floor[node]);
+    let mut path = search.goal.and_then(|goal| search.path_to(&goal)).ok_or_else(no_path)?;
+    path.reverse();
+    Ok(path)
+}
+
+/// The square a path on a heightmap `width` squares wide starts from, then its steps
+/// as `^`, `v`, `<` and `>`, e.g. `from 0,0: >v`.
+pub fn steps(path: &[Node], width: usize) -> String {
+    let arrows = path.windows(2)
+        .map(|w| match (w[0] / width, w[1] / width) {
+            (from, to) if to > from => 'v',
+            (from, to) if to < from => '^',
+            _ if w[1] > w[0] => '>',
+            _ => '<',
+        })
+        .collect::<String>();
+    match path.first() {
+        Some(&start) => format!("from {}: {arrows}", Point::new((start % width) as i64, (start / width) as i64)),
+        None => arrows,
     }
 }
 
-fn parse_graph(path: &str) -> Result<(Graph, Node, Node, Vec<bool>), Error> {
-    let (chars, width) = read_chars(path)?;
+fn no_path() -> Error {
+    Error::no_answer("no path leads to the best signal")
+}
+
+fn no_start() -> Error {
+    Error::expected("a start position `S`")
+}
+
+fn no_end() -> Error {
+    Error::expected("a best signal position `E`")
+}
+
+pub fn parse_graph(input: impl BufRead) -> Result<Terrain> {
+    let chars = read_chars(input)?;
+    let find = |c: char| chars.iter().find(|&(_, &x)| x == c).and_then(|(pos, _)| chars.index_of(pos));
+    let start = find('S').ok_or_else(no_start)?;
+    let end = find('E').ok_or_else(no_end)?;
     let mut graph = vec![];
-    let mut floor = vec![false; chars.len()];
-    let mut start = 0;
-    let mut end = 0;
-    for i in 0..chars.len() {
-        let mut prev_nodes = vec![];
-        if chars[i] == 'S' {
-            start = i;
-            floor[i] = true
-        } else if chars[i] == 'E' {
-            end = i
-        } else if chars[i] == 'a' {
-            floor[i] = true
-        }
-        if i >= width && can_move(chars[i - width], chars[i]) {
-            prev_nodes.push(i - width)
-        }
-        if i < chars.len() - width && can_move(chars[i + width], chars[i]) {
-            prev_nodes.push(i + width)
-        }
-        if i % width != 0 && can_move(chars[i - 1], chars[i]) {
-            prev_nodes.push(i - 1)
-        }
-        if i % width != (width - 1) && can_move(chars[i + 1], chars[i]) {
-            prev_nodes.push(i + 1)
-        }
+    let mut floor = vec![];
+    for (pos, &c) in chars.iter() {
+        floor.push(height(c) == height('a'));
+        let prev_nodes = chars.neighbours4(pos)
+            .filter(|&prev| can_move(chars[prev], c))
+            .filter_map(|prev| chars.index_of(prev))
+            .collect();
         graph.push(prev_nodes)
     }
-    Ok((graph, start, end, floor))
+    Ok(Terrain { graph, start, end, floor, width: chars.width() })
 }
 
 fn can_move(c1: char, c2: char) -> bool {
@@ -96,29 +122,76 @@ fn height(c: char) -> i8 {
     }
 }
 
-fn read_chars(path: &str) -> Result<(Vec<char>, usize), Error> {
-    let mut chars = vec![];
-    let mut width = 0;
-    let file = File::open(path).map_err(|e| IO(e))?;
-    for line in io::BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        width = line.len();
-        chars.append(line.chars().collect::<Vec<_>>().as_mut())
+const HEIGHT: &str = "a height `a`-`z`, `S` or `E`";
+
+fn height_char(c: char) -> Option<char> {
+    (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+}
+
+/// The heightmap as it is written, the nodes of the graph being the indices of its cells.
+pub fn read_chars(input: impl BufRead) -> Result<Grid<char>> {
+    Grid::parse(input, height_char, HEIGHT)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Terrain;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_graph(input.as_bytes())
+    }
+
+    fn part1(&self, terrain: &Self::Input) -> Result<Answer> {
+        shortest_start_end(terrain).map(Answer::from)
+    }
+
+    fn part2(&self, terrain: &Self::Input) -> Result<Answer> {
+        shortest_floor_end(terrain).map(Answer::from)
+    }
+
+    fn explain1(&self, terrain: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let path = path_start_end(terrain)?;
+        Ok(((path.len() - 1).into(), Some(steps(&path, terrain.width))))
+    }
+
+    fn explain2(&self, terrain: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let path = path_floor_end(terrain)?;
+        Ok(((path.len() - 1).into(), Some(steps(&path, terrain.width))))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        let mut problems = Grid::check(input, height_char, HEIGHT);
+        if !input.contains('S') {
+            problems.push(no_start());
+        }
+        if !input.contains('E') {
+            problems.push(no_end());
+        }
+        problems
     }
-    Ok((chars, width))
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_shortest_start_end() {
-        println!("{}", shortest_start_end().unwrap());
+        println!("{}", shortest_start_end(&parse_graph(open_input("input-12.txt").unwrap()).unwrap()).unwrap());
     }
 
     #[test]
     fn print_shortest_floor_end() {
-        println!("{}", shortest_floor_end().unwrap());
+        println!("{}", shortest_floor_end(&parse_graph(open_input("input-12.txt").unwrap()).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn steps_down_a_single_column() {
+        let column = "SbcdefghijklmnopqrstuvwxyE".chars().map(|c| format!("{c}\n")).collect::<String>();
+        let (steps, explanation) = Day12.explain1(&Day12.parse(&column).unwrap()).unwrap();
+        assert_eq!((steps, explanation), (25u64.into(), Some(format!("from 0,0: {}", "v".repeat(25)))));
     }
 }
@@ -1,19 +1,13 @@
+//! Day 13: Distress Signal.
+
 use std::cmp::Ordering;
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::FromStr;
 use Elem::*;
 
-use Error::*;
-
-#[derive(Debug)]
-pub enum Error {
-    IO(io::Error),
-    ExtraInput(String),
-    ParseElem(String),
-    NotFound(Elem)
-}
+use crate::check::check_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Elem {
@@ -21,67 +15,97 @@ pub enum Elem {
     List(Vec<Elem>),
 }
 
-fn count_right_order() -> Result<u64, Error> {
-    let mut i = 0;
-    let mut result = 0;
-    let mut l1 = Int(0);
-    let file = File::open("input-13.txt").map_err(|e| IO(e))?;
-    for line in BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        match i % 3 {
-            0 => l1 = line.parse::<Elem>()?,
-            1 => {
-                let l2 = line.parse::<Elem>()?;
-                if l1 < l2 {
-                    result += (i + 2) / 3 // add 1-based index of the pair
-                }
-            }
-            _ => ()
+pub type Pair = (Elem, Elem);
+
+// each pair takes two lines, followed by a blank one; `None` for the blank line
+fn parse_line(line_no: usize, line: &str) -> Result<Option<Elem>> {
+    if line_no.is_multiple_of(3) {
+        if !line.is_empty() {
+            return Err(Error::expected("a blank line between pairs").at(line_no, 1, line));
         }
-        i += 1;
+        Ok(None)
+    } else {
+        parse_packet(line_no, line).map(Some)
+    }
+}
+
+fn incomplete_pair() -> Error {
+    Error::expected("a second packet in the last pair")
+}
+
+pub fn read_pairs(input: impl BufRead) -> Result<Vec<Pair>> {
+    let mut result = vec![];
+    let mut l1 = None;
+    for (i, line) in input.lines().enumerate() {
+        match (parse_line(i + 1, &line?)?, l1.take()) {
+            (Some(l2), Some(l1)) => result.push((l1, l2)),
+            (packet, _) => l1 = packet,
+        }
+    }
+    if l1.is_some() {
+        return Err(incomplete_pair());
     }
     Ok(result)
 }
 
-fn decoder_key() -> Result<usize, Error> {
-    let file = File::open("input-13.txt").map_err(|e| IO(e))?;
+pub fn count_right_order(pairs: &[Pair]) -> u64 {
+    right_order_pairs(pairs).iter().map(|&i| i as u64).sum()
+}
+
+/// The 1-based indices of the pairs in the right order.
+pub fn right_order_pairs(pairs: &[Pair]) -> Vec<usize> {
+    let mut result = vec![];
+    for (i, (l1, l2)) in pairs.iter().enumerate() {
+        if l1 < l2 {
+            result.push(i + 1)
+        }
+    }
+    result
+}
+
+pub fn decoder_key(pairs: &[Pair]) -> Result<usize> {
+    divider_indices(pairs).map(|(idx1, idx2)| idx1 * idx2)
+}
+
+/// The 1-based indices of the two divider packets once all packets are sorted.
+pub fn divider_indices(pairs: &[Pair]) -> Result<(usize, usize)> {
     let p1 = List(vec![List(vec![Int(2)])]);
     let p2 = List(vec![List(vec![Int(6)])]);
     let mut packets = vec![p1.clone(), p2.clone()];
-    for line in BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        if !line.is_empty() {
-            let packet = line.parse::<Elem>()?;
-            packets.push(packet)
-        }
+    for (l1, l2) in pairs {
+        packets.push(l1.clone());
+        packets.push(l2.clone());
     }
     packets.sort();
     let idx = |p| packets.binary_search(&p)
-        .map_err(|_| NotFound(p))
+        .map_err(|_| Error::no_answer(format!("divider packet {p:?} not found")))
         .map(|i| i + 1);
     let idx1 = idx(p1.clone())?;
     let idx2 = idx(p2.clone())?;
-    Ok(idx1 * idx2)
+    Ok((idx1, idx2))
+}
+
+pub fn parse_packet(line_no: usize, line: &str) -> Result<Elem> {
+    // nom reports the unparsed rest of the input, which gives us the column
+    let column = |rest: &[u8]| line.len() - rest.len() + 1;
+    let (extra_input, elem) = parse::elem(line.as_bytes())
+        .map_err(|e| match e {
+            nom::Err::Error(e) => Error::expected("`[`, `]`, `,` or a number").at(line_no, column(e.input), line),
+            nom::Err::Failure(e) => Error::expected("a number up to 255").at(line_no, column(e.input), line),
+            nom::Err::Incomplete(_) => Error::expected("the rest of the packet").at(line_no, line.len() + 1, line),
+        })?;
+    if !extra_input.is_empty() {
+        Err(Error::expected("end of packet").at(line_no, column(extra_input), line))
+    } else {
+        Ok(elem)
+    }
 }
 
 impl FromStr for Elem {
     type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (extra_input, elem) = parse::elem(s.as_bytes())
-            .map_err(|e| {
-                let s = match e {
-                    nom::Err::Error(e) => format!("error: {}", String::from_utf8_lossy(e.input)),
-                    nom::Err::Incomplete(n) => format!("needed: {n:?}"),
-                    nom::Err::Failure(e) => format!("failure: {}", String::from_utf8_lossy(e.input)),
-                };
-                ParseElem(s.to_string())
-            })?;
-        if !extra_input.is_empty() {
-            Err(ExtraInput(String::from_utf8_lossy(extra_input).to_string()))
-        } else {
-            Ok(elem)
-        }
+    fn from_str(s: &str) -> Result<Self> {
+        parse_packet(1, s)
     }
 }
 
@@ -116,6 +140,43 @@ impl Ord for Elem {
     }
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Pair>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        read_pairs(input.as_bytes())
+    }
+
+    fn part1(&self, pairs: &Self::Input) -> Result<Answer> {
+        Ok(count_right_order(pairs).into())
+    }
+
+    fn part2(&self, pairs: &Self::Input) -> Result<Answer> {
+        decoder_key(pairs).map(Answer::from)
+    }
+
+    fn explain1(&self, pairs: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let indices = right_order_pairs(pairs);
+        let witness = format!("pairs {}", indices.iter().map(usize::to_string).collect::<Vec<_>>().join(", "));
+        Ok((indices.iter().sum::<usize>().into(), Some(witness)))
+    }
+
+    fn explain2(&self, pairs: &Self::Input) -> Result<(Answer, Option<String>)> {
+        let (idx1, idx2) = divider_indices(pairs)?;
+        Ok(((idx1 * idx2).into(), Some(format!("dividers at {idx1} and {idx2}"))))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        let mut problems = check_lines(input, |i, line| parse_line(i, line).map(drop));
+        if input.lines().count() % 3 == 1 {
+            problems.push(incomplete_pair());
+        }
+        problems
+    }
+}
+
 mod parse {
     use std::str;
 
@@ -155,16 +216,18 @@ mod parse {
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_count_right_order() {
-        println!("{}", count_right_order().unwrap())
+        println!("{}", count_right_order(&read_pairs(open_input("input-13.txt").unwrap()).unwrap()))
     }
 
     #[test]
     fn print_decoder_key() {
-        println!("{}", decoder_key().unwrap())
+        println!("{}", decoder_key(&read_pairs(open_input("input-13.txt").unwrap()).unwrap()).unwrap())
     }
 
     #[test]
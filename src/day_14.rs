@@ -1,58 +1,57 @@
-use std::io;
-use std::num::ParseIntError;
-use std::ops::Range;
-use crate::day_14::Error::ShouldNotFall;
-use crate::day_14::map::AddResult;
+//! Day 14: Regolith Reservoir.
+
+use crate::check::check_lines;
+use crate::day_14::map::{AddResult, Map};
 use crate::day_14::map::FieldContent::RestingSand;
+use crate::error::{Error, Result};
+use crate::point::Point;
+use crate::solution::{params, Answer, Solution};
+use crate::trace::event;
+
+// each rock path is a list of points joined by straight lines
+pub type RockPaths = Vec<Vec<Point>>;
 
-#[derive(Debug)]
-pub enum Error {
-    IO(io::Error),
-    EmptyCoords(String),
-    EmptyPoints,
-    ParseInt(ParseIntError),
-    CoordsOutOfBounds(usize, usize, Range<usize>, Range<usize>),
-    ShouldNotFall
+pub const SAND_SOURCE: Point = Point::new(500, 0);
+
+pub fn count_resting(paths: &[Vec<Point>], source: Point) -> Result<u32> {
+    pour_sand(&mut parse::map(paths, source)?, source)
 }
 
-fn count_resting() -> Result<u32, Error> {
-    let mut map = parse::parse("input-14.txt")?;
-    let mut result = 0;
-    loop {
-        match map.add_sand(500, 0)? {
-            AddResult::Rest => result += 1,
-            AddResult::Fall => break
-        }
+pub fn count_resting_with_floor(paths: &[Vec<Point>], source: Point) -> Result<u32> {
+    let mut map = parse::map_with_floor(paths, source)?;
+    let result = pour_sand(&mut map, source)?;
+    if map.field(source)? != RestingSand {
+        return Err(Error::no_answer("sand falls past the floor"));
     }
-    println!("{map:?}");
     Ok(result)
 }
 
-fn count_resting_with_floor() -> Result<u32, Error> {
-    let mut map = parse::parse_with_floor("input-14.txt")?;
+/// Adds sand at `source` until it falls out of the map or comes to rest at the source,
+/// and returns how much of it came to rest.
+pub fn pour_sand(map: &mut Map, source: Point) -> Result<u32> {
     let mut result = 0;
-    loop {
-        match map.add_sand(500, 0)? {
-            AddResult::Rest => result += 1,
-            AddResult::Fall => return Err(ShouldNotFall)
-        }
-        if map.field(500, 0)? == RestingSand {
+    while let AddResult::Rest = map.add_sand(source)? {
+        result += 1;
+        event!(14, Trace, "rest", resting = result);
+        // the rocks can hold the sand up to the source, which would then keep resting there
+        if map.field(source)? == RestingSand {
             break;
         }
     }
-    println!("{map:?}");
+    event!(14, Info, "poured", resting = result);
     Ok(result)
 }
 
-mod map {
-    use std::fmt::{Debug, Formatter, Write};
-    use std::ops::Range;
+pub mod map {
+    use std::fmt::{Debug, Display, Formatter};
 
     use AddResult::*;
     use FieldContent::*;
 
-    use super::Error;
-    use super::Error::CoordsOutOfBounds;
+    use crate::error::{Error, Result};
+    use crate::grid::Grid;
+    use crate::point::{BoundingBox, Point};
+    use crate::trace::event;
 
     #[derive(Clone, Copy, Eq, PartialEq)]
     pub enum FieldContent {
@@ -61,205 +60,226 @@ mod map {
         RestingSand
     }
 
+    impl Display for FieldContent {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let c = match self {
+                Empty => '.',
+                Rock => '#',
+                RestingSand => 'o'
+            };
+            write!(f, "{c}")
+        }
+    }
+
     pub enum AddResult {
         Rest,
         Fall
     }
 
+    pub enum SandStep {
+        Move(Point),
+        Rest,
+        Fall
+    }
+
     pub struct Map {
-        fields: Vec<FieldContent>,
-        x_range: Range<usize>,
-        y_range: Range<usize>
+        grid: Grid<FieldContent>
     }
 
     impl Map {
-        pub fn new(x_range: Range<usize>, y_range: Range<usize>) -> Map {
-            let width = x_range.len();
-            let height = y_range.len();
-            Map { fields: vec![Empty; width * height], x_range, y_range }
+        pub fn new(bbox: BoundingBox) -> Map {
+            let (min, max) = (bbox.min, bbox.max);
+            Map { grid: Grid::with_bounds(min.x..max.x + 1, min.y..max.y + 1, Empty) }
+        }
+
+        pub fn grid(&self) -> &Grid<FieldContent> {
+            &self.grid
         }
 
-        pub fn field(&self, x: usize, y: usize) -> Result<FieldContent, Error> {
-            let idx = self.to_vec_idx(x, y)?;
-            Ok(self.fields[idx])
+        pub fn field(&self, p: Point) -> Result<FieldContent> {
+            self.grid.get(p).copied().ok_or_else(|| self.out_of_bounds(p))
         }
 
-        pub fn set_field(&mut self, x: usize, y: usize, f: FieldContent) -> Result<(), Error> {
-            let idx = self.to_vec_idx(x, y)?;
-            self.fields[idx] = f;
-            Ok(())
+        pub fn set_field(&mut self, p: Point, f: FieldContent) -> Result<()> {
+            if self.grid.set(p, f) { Ok(()) } else { Err(self.out_of_bounds(p)) }
         }
 
-        pub fn add_sand(&mut self, start_x: usize, start_y: usize) -> Result<AddResult, Error> {
-            let mut x = start_x;
-            let mut y = start_y;
+        pub fn add_sand(&mut self, start: Point) -> Result<AddResult> {
+            let mut curr = start;
+            if !self.grid.contains(curr) {
+                return Ok(Fall)
+            }
             loop {
-                if !(self.x_range.contains(&x) && self.y_range.contains(&y)) {
-                    return Ok(Fall)
-                }
-                if !(self.y_range.contains(&(y + 1))) {
-                    return Ok(Fall)
-                }
-                if self.field(x, y + 1)? == Empty {
-                    y += 1;
-                } else if !(self.x_range.contains(&(x - 1))) {
-                    return Ok(Fall)
-                } else if self.field(x - 1, y + 1)? == Empty {
-                    x -= 1;
-                    y += 1;
-                } else if !(self.x_range.contains(&(x + 1))) {
-                    return Ok(Fall)
-                } else if self.field(x + 1, y + 1)? == Empty {
-                    x += 1;
-                    y += 1;
-                } else {
-                    self.set_field(x, y, RestingSand)?;
-                    return Ok(Rest)
+                match self.sand_step(curr) {
+                    SandStep::Move(next) => curr = next,
+                    SandStep::Rest => {
+                        event!(14, Debug, "sand", at = curr);
+                        self.grid[curr] = RestingSand;
+                        return Ok(Rest)
+                    }
+                    SandStep::Fall => return Ok(Fall),
                 }
             }
         }
 
-        fn width(&self) -> usize {
-            self.x_range.len()
+        /// Where a grain of sand falling at `curr` goes next, without changing the map.
+        pub fn sand_step(&self, curr: Point) -> SandStep {
+            // down, then down-left, then down-right
+            for dx in [0, -1, 1] {
+                let next = curr + Point::new(dx, 1);
+                match self.grid.get(next) {
+                    None => return SandStep::Fall,
+                    Some(Empty) => return SandStep::Move(next),
+                    Some(_) => ()
+                }
+            }
+            SandStep::Rest
         }
 
-        fn height(&self) -> usize {
-            self.y_range.len()
-        }
-
-        fn to_vec_idx(&self, x: usize, y: usize) -> Result<usize, Error> {
-            if !(self.x_range.contains(&x) && self.y_range.contains(&y)) {
-                Err(CoordsOutOfBounds(x, y, self.x_range.clone(), self.y_range.clone()))
-            } else {
-                Ok((y - self.y_range.start) * self.width() + (x - self.x_range.start))
-            }
+        fn out_of_bounds(&self, p: Point) -> Error {
+            Error::no_answer(format!(
+                "point {p} is outside the map {:?} x {:?}", self.grid.x_range(), self.grid.y_range()
+            ))
         }
     }
 
     impl Debug for Map {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-            for y in self.y_range.clone() {
-                for x in self.x_range.clone() {
-                    let char = match self.field(x, y).unwrap() {
-                        Empty => '.',
-                        Rock => '#',
-                        RestingSand => 'o'
-                    };
-                    f.write_char(char)?
-                }
-                f.write_char('\n')?
-            }
-            Ok(())
+            write!(f, "{}", self.grid)
         }
     }
 }
 
-mod parse {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
+pub mod parse {
+    use std::io::BufRead;
     use crate::day_14::map::FieldContent::Rock;
+    use crate::error::{Error, Locate, Result};
+    use crate::point::{BoundingBox, Point};
 
-    use super::Error::*;
-    use super::Error;
     use super::map::*;
 
-    pub(crate) fn parse(path: &str) -> Result<Map, Error> {
-        let points = parse_points(path)?;
-        let (xs, ys) = xs_ys(&points);
-        // TODO: dedupe
-        let xmin = xs.iter().min().ok_or(EmptyPoints)?.to_owned().to_owned().min(500);
-        let xmax = xs.iter().max().ok_or(EmptyPoints)?.to_owned().to_owned().max(500);
-        let ymin = ys.iter().min().ok_or(EmptyPoints)?.to_owned().to_owned().min(0);
-        let ymax = ys.iter().max().ok_or(EmptyPoints)?.to_owned().to_owned().max(0);
-        let mut map = Map::new(xmin..(xmax+1), ymin..(ymax+1));
-
-        draw_lines(points, &mut map)?;
+    fn bounding_box(paths: &[Vec<Point>], source: Point) -> Result<BoundingBox> {
+        let mut bbox = BoundingBox::of(paths.iter().flatten().copied())
+            .ok_or_else(|| Error::expected("at least one rock path"))?;
+        bbox.include(source);
+        Ok(bbox)
+    }
 
+    pub fn map(paths: &[Vec<Point>], source: Point) -> Result<Map> {
+        let mut map = Map::new(bounding_box(paths, source)?);
+        draw_lines(paths, &mut map)?;
         Ok(map)
     }
 
-    pub(crate) fn parse_with_floor(path: &str) -> Result<Map, Error> {
-        let points = parse_points(path)?;
-        let (xs, ys) = xs_ys(&points);
-
-        let ymin = ys.iter().min().ok_or(EmptyPoints)?.to_owned().to_owned().min(0);
-        let ymax = ys.iter().max().ok_or(EmptyPoints)?.to_owned().to_owned().max(0) + 2;
-        let height = ymax - ymin + 1;
-        let xmin = xs.iter().min().ok_or(EmptyPoints)?.to_owned().to_owned().min(500 - height - 1);
-        let xmax = xs.iter().max().ok_or(EmptyPoints)?.to_owned().to_owned().max(500 + height + 1);
-        let mut map = Map::new(xmin..(xmax+1), ymin..(ymax+1));
-
-        draw_lines(points, &mut map)?;
-        for x in xmin..=xmax {
-            map.set_field(x, ymax, Rock)?;
+    pub fn map_with_floor(paths: &[Vec<Point>], source: Point) -> Result<Map> {
+        let mut bbox = bounding_box(paths, source)?;
+        let floor_y = bbox.max.y + 2;
+        // sand piles up at most this far to either side of the source
+        let spread = floor_y - source.y + 2;
+        bbox.include(Point::new(source.x - spread, floor_y));
+        bbox.include(Point::new(source.x + spread, floor_y));
+        let mut map = Map::new(bbox);
+
+        draw_lines(paths, &mut map)?;
+        for x in bbox.x_range() {
+            map.set_field(Point::new(x, floor_y), Rock)?;
         }
 
         Ok(map)
     }
 
-    fn xs_ys(points: &Vec<Vec<(usize, usize)>>) -> (Vec<&usize>, Vec<&usize>) {
-        let proj = |f: fn(&(usize, usize)) -> &usize| points.iter()
-            .flat_map(|ps| ps.iter().map(f))
-            .collect::<Vec<_>>();
-        let xs = proj(|(x, _)| x);
-        let ys = proj(|(_, y)| y);
-        (xs, ys)
-    }
-
-    fn draw_lines(points: Vec<Vec<(usize, usize)>>, map: &mut Map) -> Result<(), Error> {
-        for line in points {
-            let mut ps = line.iter();
-            let mut prev = ps.next().ok_or(EmptyPoints)?;
-            loop {
-                let next = ps.next();
-                if next.is_none() {
-                    break;
-                }
-                let next = next.unwrap();
-                for x in prev.0.min(next.0)..=(prev.0.max(next.0)) {
-                    for y in prev.1.min(next.1)..=(prev.1.max(next.1)) {
-                        map.set_field(x, y, FieldContent::Rock)?;
-                    }
+    fn draw_lines(paths: &[Vec<Point>], map: &mut Map) -> Result<()> {
+        for path in paths {
+            for segment in path.windows(2) {
+                let (mut p, end) = (segment[0], segment[1]);
+                let step = (end - p).signum();
+                map.set_field(p, Rock)?;
+                while p != end {
+                    p += step;
+                    map.set_field(p, Rock)?;
                 }
-                prev = next;
             }
         }
         Ok(())
     }
 
-    fn parse_points(path: &str) -> Result<Vec<Vec<(usize, usize)>>, Error> {
-        let mut lines = vec![];
-        let file = File::open(path).map_err(|e| IO(e))?;
-        for line in BufReader::new(file).lines() {
-            let line = line.map_err(|e| IO(e))?;
-            let mut points = vec![];
-            for s in line.split(" -> ") {
-                let mut xy = s.split(",");
-                let mut next_num = || xy.next()
-                    .ok_or(EmptyCoords(line.to_string()))?
-                    .parse::<usize>()
-                    .map_err(|e| ParseInt(e));
-                let x = next_num()?;
-                let y = next_num()?;
-                points.push((x, y));
-            }
-            lines.push(points)
+    pub fn parse_path(line_no: usize, line: &str) -> Result<Vec<Point>> {
+        let mut points = vec![];
+        for s in line.split(" -> ") {
+            let (x, y) = s.split_once(',')
+                .ok_or_else(|| Error::expected("a point `X,Y`").at_token(line_no, line, s))?;
+            let x = x.parse::<i64>().at_token(line_no, line, x)?;
+            let y = y.parse::<i64>().at_token(line_no, line, y)?;
+            points.push(Point::new(x, y));
         }
-        Ok(lines)
+        if points.windows(2).any(|w| w[0].x != w[1].x && w[0].y != w[1].y) {
+            return Err(Error::expected("horizontal or vertical lines").at(line_no, 1, line));
+        }
+        Ok(points)
+    }
+
+    pub fn parse_points(input: impl BufRead) -> Result<Vec<Vec<Point>>> {
+        let mut paths = vec![];
+        for (i, line) in input.lines().enumerate() {
+            paths.push(parse_path(i + 1, &line?)?)
+        }
+        Ok(paths)
+    }
+}
+
+/// Sand pours in at `source`.
+pub struct Day14 {
+    pub source: Point,
+}
+
+impl Default for Day14 {
+    fn default() -> Self {
+        Day14 { source: SAND_SOURCE }
+    }
+}
+
+impl Solution for Day14 {
+    type Input = RockPaths;
+
+    params!(source);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse::parse_points(input.as_bytes())
+    }
+
+    fn part1(&self, paths: &Self::Input) -> Result<Answer> {
+        count_resting(paths, self.source).map(Answer::from)
+    }
+
+    fn part2(&self, paths: &Self::Input) -> Result<Answer> {
+        count_resting_with_floor(paths, self.source).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse::parse_path(i, line).map(drop))
     }
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_count_resting() {
-        println!("{}", count_resting().unwrap());
+        println!("{}", count_resting(&parse::parse_points(open_input("input-14.txt").unwrap()).unwrap(), SAND_SOURCE).unwrap());
     }
 
     #[test]
     fn print_count_resting_with_floor() {
-        println!("{}", count_resting_with_floor().unwrap());
+        println!("{}", count_resting_with_floor(&parse::parse_points(open_input("input-14.txt").unwrap()).unwrap(), SAND_SOURCE).unwrap());
+    }
+
+    #[test]
+    fn sand_stops_when_the_rocks_hold_it_up_to_the_source() {
+        // a cup right below the source fills up to it, and no sand ever falls out
+        let paths = parse::parse_points("499,1 -> 499,2 -> 501,2 -> 501,1\n".as_bytes()).unwrap();
+        assert_eq!(count_resting(&paths, SAND_SOURCE).unwrap(), 2);
     }
 }
\ No newline at end of file
@@ -1,138 +1,143 @@
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+//! Day 15: Beacon Exclusion Zone.
 
-use regex::Regex;
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::ops::Range;
 
-use Error::*;
+use regex::Regex;
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    MalformedLine(String),
-    ParseInt(ParseIntError)
-}
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::intervals::IntervalSet;
+use crate::point::Point;
+use crate::solution::{params, Answer, Solution};
 
 #[derive(Clone, Copy, Debug)]
-struct Point {
-    pub x: i64,
-    pub y: i64
+pub struct SensorBeacon {
+    pub sensor: Point,
+    pub closest_beacon: Point
 }
 
-impl Point {
-    fn distance(&self, other: &Self) -> u64 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+impl SensorBeacon {
+    fn distance(&self) -> u64 {
+        self.sensor.manhattan(self.closest_beacon)
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct RowInterval {
-    pub start_x: i64,
-    pub end_x: i64
+pub fn count_points_with_no_beacon(sensors: &[SensorBeacon], row: i64) -> u64 {
+    // the beacons in the row are covered, but are not places where there is no beacon
+    let beacons = sensors.iter()
+        .map(|s| s.closest_beacon)
+        .filter(|b| b.y == row)
+        .collect::<HashSet<_>>();
+    row_coverage(sensors, row).len() - beacons.len() as u64
 }
 
-impl RowInterval {
-    fn len(&self) -> u64 {
-        assert!(self.start_x <= self.end_x);
-        (self.end_x - self.start_x) as u64
-    }
-
-    fn combine_if_overlap(&self, other: &Self) -> Option<Self> {
-        assert!(self.start_x <= self.end_x);
-        assert!(other.start_x <= other.end_x);
-        if self.start_x >= other.start_x && self.start_x <= other.end_x ||
-            other.start_x >= self.start_x && other.start_x <= self.end_x {
-            Some(RowInterval {
-                start_x: self.start_x.min(other.start_x),
-                end_x: self.end_x.max(other.end_x)
-            })
-        } else {
-            None
+pub fn tuning_frequency(sensors: &[SensorBeacon], max_coord: i64) -> Result<i64> {
+    for y in 0..=max_coord {
+        if let Some(gap) = row_coverage(sensors, y).gaps(0..max_coord + 1).first() {
+            return Ok(gap.start * 4_000_000 + y);
         }
     }
+    Err(Error::no_answer(format!("no uncovered position in 0..={max_coord}")))
 }
 
-#[derive(Clone, Copy, Debug)]
-struct SensorBeacon {
-    pub sensor: Point,
-    pub closest_beacon: Point
+// the x coordinates covered by the sensors in the given row
+pub fn row_coverage(sensors: &[SensorBeacon], row: i64) -> IntervalSet {
+    sensors.iter()
+        .filter_map(|s| int_row_no_sensor(s, row))
+        .collect()
 }
 
-impl SensorBeacon {
-    fn distance(&self) -> u64 {
-        self.sensor.distance(&self.closest_beacon)
-    }
+fn parse_sensor(re: &Regex, line_no: usize, line: &str) -> Result<SensorBeacon> {
+    let cap = re.captures(line).ok_or_else(|| {
+        Error::expected("`Sensor at x=X, y=Y: closest beacon is at x=X, y=Y`").at(line_no, 1, line)
+    })?;
+    let num = |j: usize| cap[j].parse::<i64>().at_token(line_no, line, &line[cap.get(j).unwrap().range()]);
+    Ok(SensorBeacon {
+        sensor: Point::new(num(1)?, num(2)?),
+        closest_beacon: Point::new(num(3)?, num(4)?)
+    })
 }
 
-fn count_points_with_no_beacon() -> Result<u64, Error> {
-    let sensors = parse("input-15.txt")?;
-    let mut raw_intervals = sensors.iter()
-        .filter_map(|s| int_row_no_sensor(s, 2000000))
-        .collect::<Vec<_>>();
-    // combine overlapping
-    raw_intervals.sort_by_key(|i| i.start_x);
-    let mut combined_intervals = vec![];
-    let mut prev = raw_intervals[0];
-    for i in 1..raw_intervals.len() {
-        let curr = &raw_intervals[i];
-        if let Some(combined) = prev.combine_if_overlap(curr) {
-            prev = combined
-        } else {
-            combined_intervals.push(prev);
-            prev = curr.clone()
-        }
-    }
-    combined_intervals.push(prev);
-    // calc sum of lengths
-    let sum = combined_intervals.iter()
-        .map(|i| i.len())
-        .sum();
-    Ok(sum)
+fn sensor_regex() -> Regex {
+    Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap()
 }
 
-fn parse(path: &str) -> Result<Vec<SensorBeacon>, Error> {
+pub fn parse(input: impl BufRead) -> Result<Vec<SensorBeacon>> {
     let mut result = vec![];
-    let re = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
-    let file = File::open(path).map_err(|e| IO(e))?;
-    for line in BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        let cap = re.captures(&line[..]).ok_or(MalformedLine(line.to_string()))?;
-        let num = |i: usize| cap[i].parse::<i64>().map_err(|e| ParseInt(e));
-        result.push(SensorBeacon {
-            sensor: Point { x: num(1)?, y: num(2)? },
-            closest_beacon: Point { x: num(3)?, y: num(4)? }
-        })
+    let re = sensor_regex();
+    for (i, line) in input.lines().enumerate() {
+        result.push(parse_sensor(&re, i + 1, &line?)?);
     }
     Ok(result)
 }
 
-fn int_row_no_sensor(sb: &SensorBeacon, y: i64) -> Option<RowInterval> {
-    let beacon_dist = sb.closest_beacon.x.abs_diff(sb.sensor.x) +
-        sb.closest_beacon.y.abs_diff(sb.sensor.y);
+fn int_row_no_sensor(sb: &SensorBeacon, y: i64) -> Option<Range<i64>> {
+    let beacon_dist = sb.distance();
     let y_dist = y.abs_diff(sb.sensor.y);
     if y_dist <= beacon_dist {
-        Some(RowInterval {
-            start_x: sb.sensor.x - (beacon_dist - y_dist) as i64,
-            end_x: sb.sensor.x + (beacon_dist - y_dist) as i64
-        })
+        let x_dist = (beacon_dist - y_dist) as i64;
+        Some(sb.sensor.x - x_dist..sb.sensor.x + x_dist + 1)
     } else {
         None
     }
 }
 
+/// The row to inspect and the search area depend on the input; the example uses smaller ones.
+pub struct Day15 {
+    pub row: i64,
+    pub max_coord: i64,
+}
+
+impl Default for Day15 {
+    fn default() -> Self {
+        Day15 { row: 2000000, max_coord: 4000000 }
+    }
+}
+
+impl Solution for Day15 {
+    type Input = Vec<SensorBeacon>;
+
+    params!(row, max_coord);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, sensors: &Self::Input) -> Result<Answer> {
+        Ok(count_points_with_no_beacon(sensors, self.row).into())
+    }
+
+    fn part2(&self, sensors: &Self::Input) -> Result<Answer> {
+        tuning_frequency(sensors, self.max_coord).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        let re = sensor_regex();
+        check_lines(input, |i, line| parse_sensor(&re, i, line).map(drop))
+    }
+}
+
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_count_points_with_no_beacon() {
-        println!("{}", count_points_with_no_beacon().unwrap());
+        println!("{}", count_points_with_no_beacon(&parse(open_input("input-15.txt").unwrap()).unwrap(), 2000000));
+    }
+
+    #[test]
+    fn print_tuning_frequency() {
+        println!("{}", tuning_frequency(&parse(open_input("input-15.txt").unwrap()).unwrap(), 4000000).unwrap());
     }
 
     #[test]
     fn print_inequalities() {
-        let sensors = parse("input-15.txt").unwrap();
+        let sensors = parse(open_input("input-15.txt").unwrap()).unwrap();
         for s in sensors {
             // sympy inequalities
             println!("(Abs(x - {}) + Abs(y - {}) - {}, '>')", s.sensor.x, s.sensor.y, s.distance())
@@ -141,12 +146,12 @@ mod run {
 
     #[test]
     fn print_dist_1() {
-        let sensors = parse("input-15.txt").unwrap();
+        let sensors = parse(open_input("input-15.txt").unwrap()).unwrap();
         for i in 0..sensors.len() - 1 {
             for j in i+1..sensors.len() {
                 let s1 = &sensors[i];
                 let s2 = &sensors[j];
-                if s1.sensor.distance(&s2.sensor) == s1.distance() + s2.distance() + 2 {
+                if s1.sensor.manhattan(s2.sensor) == s1.distance() + s2.distance() + 2 {
                     println!("{},{} - {} : {},{} - {}", s1.sensor.x, s1.sensor.y, s1.distance(), s2.sensor.x, s2.sensor.y, s2.distance())
                 }
             }
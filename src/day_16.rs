@@ -1,126 +1,196 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+//! Day 16: Proboscidea Volcanium.
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
 
 use regex::Regex;
 
-use Error::*;
+use crate::check::check_lines;
+use crate::error::{Error, Locate, Result};
+use crate::graph::{bfs, floyd_warshall, Compressed};
+use crate::solution::{params, Answer, Solution};
+use crate::trace::event;
+
+pub fn max_pressure(graph: &Graph, start: &str, minutes: u32) -> Result<u64> {
+    best_schedule(graph, start, minutes).map(|(max, _)| max)
+}
+
+pub fn max_pressure_2(graph: &Graph, start: &str, num_agents: usize, minutes: u32) -> Result<u64> {
+    best_schedule_2(graph, start, num_agents, minutes).map(|(max, _)| max)
+}
 
-#[derive(Debug)]
-enum Error {
-    IO(io::Error),
-    MalformedLine(String),
-    ParseInt(ParseIntError)
+/// A valve opened by an agent, and the minute the agent is done opening it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Opening<'g> {
+    pub agent: usize,
+    pub valve: &'g str,
+    pub minute: u32,
 }
 
-fn max_pressure() -> Result<u64, Error> {
-    let (graph, start) = parse("input-16.txt")?;
-    let mut free_locs = graph.nodes();
-    free_locs.remove(start.as_str());
-    let max = max_pressure_p(0, 0, &start, free_locs, &graph, vec![&start])?;
-    Ok(max)
+/// The most pressure one agent can release from `start` in `minutes`, and the valves it opens.
+pub fn best_schedule<'g>(graph: &'g Graph, start: &str, minutes: u32) -> Result<(u64, Vec<Opening<'g>>)> {
+    let start = graph.node(start)?;
+    let free_locs = graph.reachable_from(start);
+    let (max, path) = max_pressure_p(0, minutes, 0, start, free_locs, graph, vec![(start, 0)])?;
+    let openings = path[1..].iter()
+        .map(|&(valve, minute)| Opening { agent: 0, valve, minute })
+        .collect();
+    Ok((max, openings))
 }
 
-fn max_pressure_2(num_agents: usize) -> Result<u64, Error> {
-    let (graph, start) = parse("input-16.txt")?;
-    let mut free_locs = graph.nodes();
-    free_locs.remove(start.as_str());
+/// The most pressure `num_agents` agents can release together, and the valves each of them opens.
+pub fn best_schedule_2<'g>(graph: &'g Graph, start: &str, num_agents: usize, minutes: u32) -> Result<(u64, Vec<Opening<'g>>)> {
+    let start = graph.node(start)?;
+    let free_locs = graph.reachable_from(start);
     let agents = vec![Agent { loc: start, next_move_time: 0 }; num_agents];
-    let max = max_pressure_p2(agents, 0, free_locs, &graph)?;
-    Ok(max)
+    max_pressure_p2(agents, minutes, 0, free_locs, graph, vec![])
 }
 
 #[derive(Clone, Debug)]
-struct Agent {
-    pub loc: String,
+struct Agent<'g> {
+    pub loc: &'g String,
     pub next_move_time: u32
 }
 
-fn max_pressure_p2(agents: Vec<Agent>, acc: u64, remaining_locs: HashSet<String>, graph: &Graph) -> Result<u64, Error> {
-    const TOTAL_TIME: u32 = 26;
+fn max_pressure_p2<'g>(
+    agents: Vec<Agent<'g>>,
+    total_time: u32,
+    acc: u64,
+    remaining_locs: HashSet<&'g String>,
+    graph: &'g Graph,
+    openings: Vec<Opening<'g>>,
+) -> Result<(u64, Vec<Opening<'g>>)> {
     if remaining_locs.is_empty() {
-        return Ok(acc)
+        return Ok((acc, openings))
     }
 
     let mut next_agent = 0;
     let mut time = u32::MAX;
-    for i in 0..agents.len() {
-        if agents[i].next_move_time < time {
+    for (i, agent) in agents.iter().enumerate() {
+        if agent.next_move_time < time {
             next_agent = i;
-            time = agents[i].next_move_time;
+            time = agent.next_move_time;
         }
     }
     let next_agent = next_agent;
-    let loc = &agents[next_agent].loc;
+    let loc = agents[next_agent].loc;
 
-    let mut totals = HashSet::new();
-    for next_loc in &remaining_locs {
+    let mut best: Option<(u64, Vec<Opening>)> = None;
+    for &next_loc in &remaining_locs {
+        // tunnels may lead one way only, so a valve reached from the start is not always reached from here
+        let Some(move_time) = graph.move_time(loc, next_loc) else { continue };
         let mut next_remaining_locs = remaining_locs.clone();
         next_remaining_locs.remove(next_loc);
-        let time_diff = graph.move_time(loc, next_loc) + 1;
+        let time_diff = move_time + 1;
         let next_time = time + time_diff;
-        if next_time < TOTAL_TIME {
-            let next_acc = acc + graph.flow_rate(next_loc) * (TOTAL_TIME - next_time) as u64;
+        if next_time < total_time {
+            let next_acc = acc + graph.flow_rate(next_loc) * (total_time - next_time) as u64;
             let mut next_agents = agents.clone();
-            next_agents[next_agent] = Agent { loc: next_loc.to_owned(), next_move_time: next_time };
-            let path_max = max_pressure_p2(next_agents, next_acc, next_remaining_locs, graph)?;
-            totals.insert(path_max);
+            next_agents[next_agent] = Agent { loc: next_loc, next_move_time: next_time };
+            let mut next_openings = openings.clone();
+            next_openings.push(Opening { agent: next_agent, valve: next_loc, minute: next_time });
+            let (path_max, path) = max_pressure_p2(next_agents, total_time, next_acc, next_remaining_locs, graph, next_openings)?;
+            event!(16, Trace, "branch", agent = next_agent, from = loc, time = time, to = next_loc, arrival = next_time, best = path_max);
+            if best.as_ref().is_none_or(|&(max, _)| path_max > max) {
+                best = Some((path_max, path));
+            }
         }
     }
-    if let Some(max) = totals.iter().max() {
-        Ok(max.to_owned())
-    } else {
-        Ok(acc)
-    }
+    Ok(best.unwrap_or((acc, openings)))
 }
 
-fn max_pressure_p(time: u32, acc: u64, loc: &String, remaining_locs: HashSet<String>, graph: &Graph, prefix: Vec<&String>) -> Result<u64, Error> {
-    const TOTAL_TIME: u32 = 30;
+// `prefix` holds the start at minute 0, then each valve opened so far with the minute it is open
+fn max_pressure_p<'g>(
+    time: u32,
+    total_time: u32,
+    acc: u64,
+    loc: &'g String,
+    remaining_locs: HashSet<&'g String>,
+    graph: &'g Graph,
+    prefix: Vec<(&'g String, u32)>,
+) -> Result<(u64, Vec<(&'g String, u32)>)> {
     if remaining_locs.is_empty() {
-        //println!("{acc}: {prefix:?}");
-        return Ok(acc)
+        event!(16, Debug, "path", pressure = acc, valves = path_names(&prefix));
+        return Ok((acc, prefix))
     }
-    let mut totals = HashSet::new();
-    for next_loc in &remaining_locs {
+    let mut best: Option<(u64, Vec<(&String, u32)>)> = None;
+    for &next_loc in &remaining_locs {
+        // tunnels may lead one way only, so a valve reached from the start is not always reached from here
+        let Some(move_time) = graph.move_time(loc, next_loc) else { continue };
         let mut next_remaining_locs = remaining_locs.clone();
         next_remaining_locs.remove(next_loc);
-        let time_diff = graph.move_time(loc, next_loc) + 1;
+        let time_diff = move_time + 1;
         let next_time = time + time_diff;
-        if next_time < TOTAL_TIME {
-            let next_acc = acc + graph.flow_rate(next_loc) * (TOTAL_TIME - next_time) as u64;
+        if next_time < total_time {
+            let next_acc = acc + graph.flow_rate(next_loc) * (total_time - next_time) as u64;
             let mut next_prefix = prefix.clone();
-            next_prefix.push(next_loc);
-            let path_max = max_pressure_p(next_time, next_acc, next_loc, next_remaining_locs, graph, next_prefix)?;
-            totals.insert(path_max);
-            //println!("{loc}:{time} -> {next_loc}:{next_time}.. = {path_max}")
+            next_prefix.push((next_loc, next_time));
+            let (path_max, path) = max_pressure_p(next_time, total_time, next_acc, next_loc, next_remaining_locs, graph, next_prefix)?;
+            event!(16, Trace, "branch", from = loc, time = time, to = next_loc, arrival = next_time, best = path_max);
+            if best.as_ref().is_none_or(|&(max, _)| path_max > max) {
+                best = Some((path_max, path));
+            }
         }
     }
-    if let Some(max) = totals.iter().max() {
-        Ok(max.to_owned())
-    } else {
-        //println!("{acc}: {prefix:?}");
-        Ok(acc)
+    match best {
+        Some(best) => Ok(best),
+        None => {
+            event!(16, Debug, "path", pressure = acc, valves = path_names(&prefix));
+            Ok((acc, prefix))
+        }
+    }
+}
+
+fn path_names(path: &[(&String, u32)]) -> String {
+    path.iter().map(|(valve, _)| valve.as_str()).collect::<Vec<_>>().join(",")
+}
+
+/// The valves each agent opens, one line per agent: `you` for the first, then the elephants.
+pub fn schedule_text(openings: &[Opening], num_agents: usize) -> String {
+    let mut lines = vec![];
+    for agent in 0..num_agents {
+        let name = match agent {
+            0 => "you".to_string(),
+            1 if num_agents == 2 => "elephant".to_string(),
+            _ => format!("elephant {agent}"),
+        };
+        let valves = openings.iter()
+            .filter(|o| o.agent == agent)
+            .map(|o| format!("{} at {}", o.valve, o.minute))
+            .collect::<Vec<_>>();
+        lines.push(format!("{name}: {}", if valves.is_empty() { "-".to_string() } else { valves.join(", ") }));
     }
+    lines.join("\n")
 }
 
-struct Graph {
-    move_times: HashMap<String, HashMap<String, u32>>,
+pub struct Graph {
+    valves: Compressed<String>,
     flow_rates: HashMap<String, u64>
 }
 
 impl Graph {
-    pub fn new(move_times: HashMap<String, HashMap<String, u32>>, flow_rates: HashMap<String, u64>) -> Self {
-        Graph { move_times, flow_rates }
+    pub fn new(valves: Compressed<String>, flow_rates: HashMap<String, u64>) -> Self {
+        Graph { valves, flow_rates }
     }
 
-    pub fn nodes(&self) -> HashSet<String> {
-        HashSet::from_iter(self.move_times.keys().map(|s| s.to_owned()))
+    pub fn nodes(&self) -> HashSet<&String> {
+        HashSet::from_iter(self.valves.nodes())
     }
 
-    pub fn move_time(&self, from: &String, to: &String) -> u32 {
-        self.move_times[from][to]
+    // the graph's own copy of a valve name, so that paths can borrow it from the graph
+    fn node(&self, name: &str) -> Result<&String> {
+        self.valves.nodes().find(|node| *node == name)
+            .ok_or_else(|| Error::no_answer(format!("valve {name} is not in the graph")))
+    }
+
+    /// The valves other than `start` that the tunnels lead to from it; the others can never be opened.
+    pub fn reachable_from(&self, start: &String) -> HashSet<&String> {
+        self.nodes().into_iter().filter(|&node| node != start && self.move_time(start, node).is_some()).collect()
+    }
+
+    /// The minutes it takes to walk from `from` to `to`, or `None` if no tunnels lead there.
+    pub fn move_time(&self, from: &String, to: &String) -> Option<u32> {
+        self.valves.distance(from, to).map(|d| d as u32)
     }
 
     pub fn flow_rate(&self, node: &String) -> u64 {
@@ -128,79 +198,162 @@ impl Graph {
     }
 }
 
-fn parse(path: &str) -> Result<(Graph, String), Error> {
+fn valve_regex() -> Regex {
+    Regex::new(r"Valve (\w{2}) has flow rate=(\d+); tunnels? leads? to valves? ([\s\w,]+)").unwrap()
+}
+
+// the valve's name, flow rate and the valves its tunnels lead to
+fn parse_valve(re: &Regex, line_no: usize, line: &str) -> Result<(String, u64, Vec<String>)> {
+    let cap = re.captures(line).ok_or_else(|| {
+        Error::expected("`Valve XX has flow rate=N; tunnels lead to valves YY, ZZ`").at(line_no, 1, line)
+    })?;
+    let flow = cap[2].parse::<u64>()
+        .at_token(line_no, line, &line[cap.get(2).unwrap().range()])?;
+    let next_nodes = cap[3].split(", ")
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    Ok((cap[1].to_string(), flow, next_nodes))
+}
+
+pub const START_NODE: &str = "AA";
+pub const MINUTES: u32 = 30;
+pub const MINUTES_WITH_ELEPHANT: u32 = 26;
+
+// tunnels to valves that are not described, which can only be found once all valves are known
+fn tunnel_problems(edges: &HashMap<String, Vec<String>>, start: &str) -> Vec<Error> {
+    let mut problems = vec![];
+    let mut nodes = edges.keys().collect::<Vec<_>>();
+    nodes.sort();
+    for node in nodes {
+        for next in edges[node].iter().filter(|n| !edges.contains_key(*n)) {
+            problems.push(Error::expected(format!("a description of valve {next}, reachable from {node}")));
+        }
+    }
+    if !edges.contains_key(start) {
+        problems.push(Error::expected(format!("a description of the start valve {start}")));
+    }
+    problems
+}
+
+// valves with flow that no tunnels lead to from the start, each with its line, which the
+// solver accepts but can never open
+fn unreachable_valves(edges: &HashMap<String, Vec<String>>, valves: &HashMap<String, (u64, usize, String)>, start: &str) -> Vec<Error> {
+    let search = bfs(edges, start.to_string(), |_| false);
+    let mut unreachable = valves.iter()
+        .filter(|&(valve, &(flow, _, _))| flow > 0 && !search.distances().contains_key(valve))
+        .collect::<Vec<_>>();
+    unreachable.sort_by_key(|&(_, &(_, line_no, _))| line_no);
+    unreachable.into_iter()
+        .map(|(valve, (flow, line_no, line))| {
+            Error::expected(format!("a way from {start} to valve {valve}, whose flow rate is {flow}")).at(*line_no, 1, line)
+        })
+        .collect()
+}
+
+pub fn parse(input: impl BufRead) -> Result<(Graph, String)> {
+    parse_with_start(input, START_NODE)
+}
+
+pub fn parse_with_start(input: impl BufRead, start: &str) -> Result<(Graph, String)> {
     let mut flows = HashMap::new();
     let mut edges = HashMap::new();
-    let re = Regex::new(r"Valve (\w{2}) has flow rate=(\d+); tunnels? leads? to valves? ([\s\w,]+)").unwrap();
-    let file = File::open(path).map_err(|e| IO(e))?;
-    for line in BufReader::new(file).lines() {
-        let line = line.map_err(|e| IO(e))?;
-        let cap = re.captures(&line[..]).ok_or(MalformedLine(line.to_string()))?;
-        let node = cap[1].to_string();
-        let flow = cap[2].parse::<u64>()
-            .map_err(ParseInt)?;
-        let next_nodes = cap[3].split(", ")
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
+    let re = valve_regex();
+    for (i, line) in input.lines().enumerate() {
+        let (node, flow, next_nodes) = parse_valve(&re, i + 1, &line?)?;
         flows.insert(node.clone(), flow);
-        edges.insert(node.clone(), next_nodes);
+        edges.insert(node, next_nodes);
+    }
+    if let Some(problem) = tunnel_problems(&edges, start).into_iter().next() {
+        return Err(problem);
     }
-    let start_node = "AA".to_string();
-    let mut interesting_nodes = vec![&start_node];
+    let start_node = start.to_string();
+    let mut interesting_nodes = vec![start_node.clone()];
     for (node, flow) in &flows {
         if flow > &0 {
-            interesting_nodes.push(node);
+            interesting_nodes.push(node.clone());
         }
     }
-    let move_times = calc_move_times(interesting_nodes, edges);
-    //println!("{move_times:?}");
-    Ok((Graph::new(move_times, flows), start_node))
-}
-
-fn calc_move_times(nodes: Vec<&String>, edges: HashMap<String, Vec<String>>) -> HashMap<String, HashMap<String, u32>> {
-    let mut result = HashMap::new();
-    for node in nodes.clone() {
-        let node_times = calc_move_times_from_node(node, &nodes, &edges);
-        result.insert(node.to_owned(), node_times);
-    }
-    result
-}
-
-fn calc_move_times_from_node(node: &String, nodes: &Vec<&String>, edges: &HashMap<String, Vec<String>>) -> HashMap<String, u32> {
-    let mut visited= HashSet::from([node]);
-    let mut queue = VecDeque::from([node]);
-    let mut result = HashMap::new();
-    result.insert(node.to_owned(), 0);
-    while !queue.is_empty() {
-        let curr = queue.pop_front().unwrap();
-        let curr_dist = result[curr];
-        for next in &edges[curr] {
-            if !visited.contains(next) {
-                visited.insert(next);
-                result.insert(next.to_owned(), curr_dist + 1);
-                queue.push_back(next);
-            }
-        }
+    // only the valves worth opening matter, the others are just a way through
+    let valves = floyd_warshall(&edges, edges.keys().cloned()).compress(&interesting_nodes);
+    Ok((Graph::new(valves, flows), start_node))
+}
+
+/// The valves are opened from `start`, in `minutes` alone or in `minutes_with_elephant`
+/// with the elephant's help.
+pub struct Day16 {
+    pub start: String,
+    pub minutes: u32,
+    pub minutes_with_elephant: u32,
+}
+
+impl Default for Day16 {
+    fn default() -> Self {
+        Day16 { start: START_NODE.to_string(), minutes: MINUTES, minutes_with_elephant: MINUTES_WITH_ELEPHANT }
+    }
+}
+
+impl Solution for Day16 {
+    type Input = (Graph, String);
+
+    params!(start, minutes, minutes_with_elephant);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_with_start(input.as_bytes(), &self.start)
+    }
+
+    fn part1(&self, (graph, start): &Self::Input) -> Result<Answer> {
+        max_pressure(graph, start, self.minutes).map(Answer::from)
+    }
+
+    fn part2(&self, (graph, start): &Self::Input) -> Result<Answer> {
+        max_pressure_2(graph, start, 2, self.minutes_with_elephant).map(Answer::from)
     }
-    for (k, v) in result.clone() {
-        if !nodes.contains(&&k) || v == 0 {
-            result.remove(&k);
+
+    fn explain1(&self, (graph, start): &Self::Input) -> Result<(Answer, Option<String>)> {
+        let (max, openings) = best_schedule(graph, start, self.minutes)?;
+        Ok((max.into(), Some(schedule_text(&openings, 1))))
+    }
+
+    fn explain2(&self, (graph, start): &Self::Input) -> Result<(Answer, Option<String>)> {
+        let (max, openings) = best_schedule_2(graph, start, 2, self.minutes_with_elephant)?;
+        Ok((max.into(), Some(schedule_text(&openings, 2))))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        let re = valve_regex();
+        let mut edges = HashMap::new();
+        let mut valves = HashMap::new();
+        let mut problems = check_lines(input, |i, line| {
+            let (node, flow, next_nodes) = parse_valve(&re, i, line)?;
+            valves.insert(node.clone(), (flow, i, line.to_string()));
+            edges.insert(node, next_nodes);
+            Ok(())
+        });
+        if problems.is_empty() {
+            problems = tunnel_problems(&edges, &self.start);
+        }
+        if problems.is_empty() {
+            problems = unreachable_valves(&edges, &valves, &self.start);
         }
+        problems
     }
-    result
 }
 
 #[cfg(test)]
 mod run {
+    use crate::solution::open_input;
+
     use super::*;
 
     #[test]
     fn print_max_pressure() {
-        println!("{}", max_pressure().unwrap());
+        let (graph, start) = parse(open_input("input-16.txt").unwrap()).unwrap();
+        println!("{}", max_pressure(&graph, &start, MINUTES).unwrap());
     }
 
     #[test]
     fn print_max_pressure_2() {
-        println!("{}", max_pressure_2(2).unwrap());
+        let (graph, start) = parse(open_input("input-16.txt").unwrap()).unwrap();
+        println!("{}", max_pressure_2(&graph, &start, 2, MINUTES_WITH_ELEPHANT).unwrap());
     }
 }
\ No newline at end of file
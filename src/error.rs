@@ -0,0 +1,227 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::num::ParseIntError;
+
+use crate::json::Json;
+
+/// What went wrong, regardless of where in the input.
+#[derive(Debug)]
+pub enum ErrorKind {
+    IO(io::Error),
+    ParseInt(ParseIntError),
+    /// the input does not have the expected shape; holds a description of what was expected
+    Expected(String),
+    /// the input is well-formed, but the puzzle has no answer for it
+    NoAnswer(String),
+    /// there is no solution for this day and part
+    NoSuchPuzzle(u8, u8),
+    /// the solution panicked; holds the panic message
+    Panic(String),
+}
+
+/// Error shared by all days, pointing at the offending place in the input where known.
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub day: Option<u8>,
+    /// name of the input, usually the file path
+    pub input: Option<String>,
+    /// 1-based line number
+    pub line: Option<usize>,
+    /// 1-based column, counted in characters
+    pub column: Option<usize>,
+    /// the offending line
+    pub text: Option<String>,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Error { kind, day: None, input: None, line: None, column: None, text: None }
+    }
+
+    pub fn expected(what: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Expected(what.into()))
+    }
+
+    pub fn no_answer(why: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NoAnswer(why.into()))
+    }
+
+    /// Locates the error at a column of the given line, unless it is already located.
+    pub fn at(mut self, line_no: usize, column: usize, line: &str) -> Self {
+        if self.line.is_none() {
+            self.line = Some(line_no);
+            self.column = Some(column);
+            self.text = Some(line.to_string());
+        }
+        self
+    }
+
+    /// Locates the error at `token`, which should be a slice of `line`.
+    pub fn at_token(self, line_no: usize, line: &str, token: &str) -> Self {
+        self.at(line_no, column_of(line, token), line)
+    }
+
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn in_input(mut self, name: &str) -> Self {
+        self.input = Some(name.to_string());
+        self
+    }
+
+    /// The error with each of its fields, for other tools to read.
+    pub fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("kind", self.kind.name().into()),
+            ("message", self.kind.to_string().into()),
+            ("day", self.day.into()),
+            ("input", self.input.clone().into()),
+            ("line", self.line.into()),
+            ("column", self.column.into()),
+            ("text", self.text.clone().into()),
+        ])
+    }
+
+    /// The offending line with a caret under the column, to show below the message:
+    ///
+    /// ```text
+    /// 12 | move 1 from x to 2
+    ///    |             ^
+    /// ```
+    pub fn snippet(&self) -> Option<String> {
+        let (line_no, text) = (self.line?, self.text.as_ref()?);
+        let gutter = line_no.to_string().len();
+        let column = self.column.unwrap_or(1);
+        Some(format!("{line_no} | {text}\n{:gutter$} | {:>column$}", "", "^"))
+    }
+}
+
+fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    let byte_offset = if offset <= line.len() && line.is_char_boundary(offset) {
+        offset
+    } else {
+        line.find(token).unwrap_or(0)
+    };
+    line[..byte_offset].chars().count() + 1
+}
+
+impl ErrorKind {
+    /// Short name of the variant, e.g. `no_answer`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::IO(_) => "io",
+            ErrorKind::ParseInt(_) => "parse_int",
+            ErrorKind::Expected(_) => "expected",
+            ErrorKind::NoAnswer(_) => "no_answer",
+            ErrorKind::NoSuchPuzzle(_, _) => "no_such_puzzle",
+            ErrorKind::Panic(_) => "panic",
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::IO(e) => write!(f, "{e}"),
+            ErrorKind::ParseInt(e) => write!(f, "expected a number ({e})"),
+            ErrorKind::Expected(what) => write!(f, "expected {what}"),
+            ErrorKind::NoAnswer(why) => write!(f, "{why}"),
+            ErrorKind::NoSuchPuzzle(day, part) => write!(f, "no solution for day {day} part {part}"),
+            ErrorKind::Panic(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.input, self.day) {
+            (Some(input), _) => write!(f, "{input}:")?,
+            (None, Some(day)) => write!(f, "day {day:02}:")?,
+            (None, None) => (),
+        }
+        if let Some(line) = self.line {
+            write!(f, "{line}:")?;
+            if let Some(column) = self.column {
+                write!(f, "{column}:")?;
+            }
+        }
+        if self.input.is_some() || self.day.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::IO(e) => Some(e),
+            ErrorKind::ParseInt(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::new(ErrorKind::IO(e))
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Self::new(ErrorKind::ParseInt(e))
+    }
+}
+
+/// Attaches input locations to the errors of fallible parsing steps.
+pub trait Locate<T> {
+    fn at_line(self, line_no: usize, line: &str) -> Result<T>;
+
+    fn at_token(self, line_no: usize, line: &str, token: &str) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Locate<T> for std::result::Result<T, E> {
+    fn at_line(self, line_no: usize, line: &str) -> Result<T> {
+        self.map_err(|e| e.into().at(line_no, 1, line))
+    }
+
+    fn at_token(self, line_no: usize, line: &str, token: &str) -> Result<T> {
+        self.map_err(|e| e.into().at_token(line_no, line, token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_with_location() {
+        let line = "move 1 from x to 2";
+        let e = Error::expected("`move N from A to B`")
+            .at_token(12, line, &line[12..13])
+            .in_input("input-05.txt")
+            .in_day(5);
+        assert_eq!(e.to_string(), "input-05.txt:12:13: expected `move N from A to B`");
+    }
+
+    #[test]
+    fn snippet_points_at_column() {
+        let line = "move 1 from x to 2";
+        let e = Error::expected("a number").at_token(12, line, &line[12..13]);
+        assert_eq!(e.snippet().unwrap(), "12 | move 1 from x to 2\n   |             ^");
+        assert_eq!(Error::expected("a number").snippet(), None);
+    }
+
+    #[test]
+    fn display_without_input() {
+        let e = Error::no_answer("no marker found").in_day(6);
+        assert_eq!(e.to_string(), "day 06: no marker found");
+    }
+}
@@ -0,0 +1,73 @@
+//! The worked examples from the puzzle statements, checked against their published answers.
+//!
+//! Each day's example input lives next to the real input, in `example-NN.txt`.
+
+use std::fmt::Display;
+use std::fs::read_to_string;
+
+use crate::solution::Solution;
+
+fn example_path(day: &str) -> String {
+    // module names are `day_NN`
+    format!("example-{}.txt", day.trim_start_matches("day_"))
+}
+
+fn check<S: Solution>(solution: &S, day: &str, part: u8, expected: impl Display) {
+    let path = example_path(day);
+    let text = read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+    let input = solution.parse(&text).unwrap_or_else(|e| panic!("{}", e.in_input(&path)));
+    let answer = match part {
+        1 => solution.part1(&input),
+        _ => solution.part2(&input),
+    };
+    let answer = answer.unwrap_or_else(|e| panic!("{}", e.in_input(&path)));
+    assert_eq!(answer.to_string(), expected.to_string(), "{day} part {part}");
+}
+
+/// Generates a `part1` and a `part2` test for each `day_NN: solution => answer1, answer2;` line.
+/// Attributes before a line, e.g. `#[ignore]`, apply to both tests.
+macro_rules! examples {
+    ($($(#[$attr:meta])* $day:ident: $solution:expr => $part1:expr, $part2:expr;)*) => {
+        $(mod $day {
+            use crate::$day::*;
+
+            #[test]
+            $(#[$attr])*
+            fn part1() {
+                super::check(&$solution, stringify!($day), 1, $part1)
+            }
+
+            #[test]
+            $(#[$attr])*
+            fn part2() {
+                super::check(&$solution, stringify!($day), 2, $part2)
+            }
+        })*
+    };
+}
+
+examples! {
+    day_01: Day01 => 24000, 45000;
+    day_02: Day02 => 15, 12;
+    day_03: Day03 => 157, 70;
+    day_04: Day04 => 2, 4;
+    day_05: Day05::default() => "CMZ", "MCD";
+    day_06: Day06 => 7, 19;
+    day_07: Day07::default() => 95437, 24933642;
+    day_08: Day08 => 21, 8;
+    day_09: Day09 => 13, 1;
+    day_10: Day10::default() => 13140, concat!(
+        "\n##  ##  ##  ##  ##  ##  ##  ##  ##  ##  ",
+        "\n###   ###   ###   ###   ###   ###   ### ",
+        "\n####    ####    ####    ####    ####    ",
+        "\n#####     #####     #####     #####     ",
+        "\n######      ######      ######      ####",
+        "\n#######       #######       #######     ",
+    );
+    day_11: Day11 => 10605, 2713310158u64;
+    day_12: Day12 => 31, 29;
+    day_13: Day13 => 13, 140;
+    day_14: Day14::default() => 24, 93;
+    day_15: Day15 { row: 10, max_coord: 20 } => 26, 56000011;
+    day_16: Day16::default() => 1651, 1707;
+}
@@ -0,0 +1,490 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::ops::Range;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::day_11;
+use crate::error::{Error, ErrorKind, Result};
+use crate::grid::Grid;
+use crate::point::Point;
+
+pub const DEFAULT_SIZE: usize = 50;
+
+/// Generates a random input for `day` that its parser accepts, and for which both parts
+/// have an answer. `size` is roughly the number of items described, e.g. elves, rounds or
+/// monkeys; see the generator of each day for what it means there.
+///
+/// The same seed always gives the same input.
+pub fn generate(day: u8, seed: u64, size: usize) -> Result<String> {
+    let rng = &mut ChaCha8Rng::seed_from_u64(seed);
+    let input = match day {
+        1 => calories(rng, size),
+        2 => strategy_guide(rng, size),
+        3 => rucksacks(rng, size),
+        4 => section_pairs(rng, size),
+        5 => crane_procedure(rng, size),
+        6 => datastream(rng, size),
+        7 => terminal_output(rng, size),
+        8 => tree_heights(rng, size),
+        9 => rope_motions(rng, size),
+        10 => program(rng, size),
+        11 => monkey_notes(rng, size),
+        12 => heightmap(rng, size),
+        13 => packet_pairs(rng, size),
+        14 => rock_paths(rng, size),
+        15 => sensor_reports(rng, size),
+        16 => valve_scan(rng, size),
+        _ => return Err(Error::new(ErrorKind::NoSuchPuzzle(day, 1))),
+    };
+    Ok(input)
+}
+
+fn letter(rng: &mut impl Rng, letters: &str) -> char {
+    *letters.as_bytes().choose(rng).unwrap() as char
+}
+
+/// Day 1: `size` elves, at least three, each carrying up to 10 food items.
+pub fn calories(rng: &mut impl Rng, size: usize) -> String {
+    let elves = (0..size.max(3)).map(|_| {
+        (0..rng.gen_range(1..=10)).map(|_| format!("{}\n", rng.gen_range(1000..=20000))).collect::<String>()
+    });
+    elves.collect::<Vec<_>>().join("\n")
+}
+
+/// Day 2: `size` rounds.
+pub fn strategy_guide(rng: &mut impl Rng, size: usize) -> String {
+    (0..size).map(|_| format!("{} {}\n", letter(rng, "ABC"), letter(rng, "XYZ"))).collect()
+}
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Day 3: `size` rucksacks, rounded up to whole teams of three. Each rucksack has exactly one
+/// item type in both compartments, and each team exactly one item type in all three rucksacks.
+pub fn rucksacks(rng: &mut impl Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let mut items = ITEMS.chars().collect::<Vec<_>>();
+        items.shuffle(rng);
+        let badge = items.pop().unwrap();
+        // every other item type is left out of one of the rucksacks, so it is not common to the team
+        let mut allowed = vec![vec![]; 3];
+        for item in items {
+            let left_out = rng.gen_range(0..3);
+            for (r, allowed) in allowed.iter_mut().enumerate() {
+                if r != left_out {
+                    allowed.push(item);
+                }
+            }
+        }
+        for mut others in allowed {
+            others.shuffle(rng);
+            // the item type in both compartments may be the badge, or another one
+            let shared = if rng.gen_bool(0.1) { badge } else { others.pop().unwrap() };
+            let (n1, n2) = (rng.gen_range(1..=8), rng.gen_range(1..=8));
+            let mut first = others[..n1].to_vec();
+            let mut second = others[n1..n1 + n2].to_vec();
+            if shared != badge {
+                if rng.gen_bool(0.5) { first.push(badge) } else { second.push(badge) }
+            }
+            first.push(shared);
+            second.push(shared);
+            let len = first.len().max(second.len()) + rng.gen_range(0..=8);
+            writeln!(input, "{}{}", compartment(rng, &first, len), compartment(rng, &second, len)).unwrap();
+        }
+    }
+    input
+}
+
+// `len` items, with each of `types` at least once
+fn compartment(rng: &mut impl Rng, types: &[char], len: usize) -> String {
+    let mut items = types.to_vec();
+    while items.len() < len {
+        items.push(*types.choose(rng).unwrap());
+    }
+    items.shuffle(rng);
+    items.into_iter().collect()
+}
+
+/// Day 4: `size` pairs of section ranges between 1 and 99.
+pub fn section_pairs(rng: &mut impl Rng, size: usize) -> String {
+    let mut section_range = || {
+        let start = rng.gen_range(1..=99);
+        format!("{start}-{}", rng.gen_range(start..=99))
+    };
+    (0..size).map(|_| format!("{},{}\n", section_range(), section_range())).collect()
+}
+
+/// Day 5: 9 stacks of up to 8 crates, and `size` moves that never take more crates
+/// from a stack than it holds.
+pub fn crane_procedure(rng: &mut impl Rng, size: usize) -> String {
+    const STACKS: usize = 9;
+    let stacks = (0..STACKS)
+        .map(|_| (0..rng.gen_range(1..=8)).map(|_| rng.gen_range('A'..='Z')).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut input = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    for level in (0..height).rev() {
+        let crates = stacks.iter()
+            .map(|stack| stack.get(level).map_or("   ".to_string(), |c| format!("[{c}]")))
+            .collect::<Vec<_>>();
+        writeln!(input, "{}", crates.join(" ")).unwrap();
+    }
+    let numbers = (1..=STACKS).map(|n| format!(" {n} ")).collect::<Vec<_>>();
+    writeln!(input, "{}\n", numbers.join(" ")).unwrap();
+    // both cranes move the same number of crates, so only the heights matter
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    for _ in 0..size {
+        let from = loop {
+            let stack = rng.gen_range(0..STACKS);
+            if heights[stack] > 0 {
+                break stack;
+            }
+        };
+        let to = (from + rng.gen_range(1..STACKS)) % STACKS;
+        let num_crates = rng.gen_range(1..=heights[from]);
+        heights[from] -= num_crates;
+        heights[to] += num_crates;
+        writeln!(input, "move {num_crates} from {} to {}", from + 1, to + 1).unwrap();
+    }
+    input
+}
+
+/// Day 6: a datastream of `size` letters, at least 14, with a single run of 14 different
+/// letters at a random position.
+pub fn datastream(rng: &mut impl Rng, size: usize) -> String {
+    let mut letters = ('a'..='z').collect::<Vec<_>>();
+    letters.shuffle(rng);
+    // too few letters for a start-of-message marker, until one is put in
+    let alphabet = &letters[..rng.gen_range(2..14)];
+    let mut stream = (0..size.max(14)).map(|_| *alphabet.choose(rng).unwrap()).collect::<Vec<_>>();
+    letters.shuffle(rng);
+    let at = rng.gen_range(0..=stream.len() - 14);
+    stream[at..at + 14].copy_from_slice(&letters[..14]);
+    stream.into_iter().chain(['\n']).collect()
+}
+
+#[derive(Default)]
+struct Directory {
+    name: String,
+    dirs: Vec<usize>,
+    files: Vec<(String, u64)>,
+}
+
+impl Directory {
+    fn has_entry(&self, name: &str, dirs: &[Directory]) -> bool {
+        self.files.iter().any(|(file, _)| file == name) || self.dirs.iter().any(|&d| dirs[d].name == name)
+    }
+}
+
+/// Day 7: a terminal session exploring a file system of `size` files in random directories,
+/// small enough to fit on the disk.
+pub fn terminal_output(rng: &mut impl Rng, size: usize) -> String {
+    let max_file_size = (60_000_000 / size.max(1) as u64).min(300_000);
+    let mut dirs = vec![Directory::default()];
+    for _ in 0..size {
+        if rng.gen_bool(0.3) {
+            let parent = rng.gen_range(0..dirs.len());
+            let name = entry_name(rng, &dirs[parent], &dirs, false);
+            dirs.push(Directory { name, ..Directory::default() });
+            let dir = dirs.len() - 1;
+            dirs[parent].dirs.push(dir);
+        }
+        let parent = rng.gen_range(0..dirs.len());
+        let name = entry_name(rng, &dirs[parent], &dirs, true);
+        // mostly small files, so that there are small directories too
+        let max = (max_file_size >> rng.gen_range(0..8)).max(1);
+        dirs[parent].files.push((name, rng.gen_range(1..=max)));
+    }
+    let mut input = "$ cd /\n".to_string();
+    list_directory(rng, &dirs, 0, &mut input);
+    input
+}
+
+// a name not yet used in `dir`, with an extension for files
+fn entry_name(rng: &mut impl Rng, dir: &Directory, dirs: &[Directory], file: bool) -> String {
+    loop {
+        let mut name = (0..rng.gen_range(1..=8)).map(|_| rng.gen_range('a'..='z')).collect::<String>();
+        if file && rng.gen_bool(0.5) {
+            name.push('.');
+            name.extend((0..3).map(|_| rng.gen_range('a'..='z')));
+        }
+        if !dir.has_entry(&name, dirs) {
+            return name;
+        }
+    }
+}
+
+fn list_directory(rng: &mut impl Rng, dirs: &[Directory], dir: usize, input: &mut String) {
+    let dir = &dirs[dir];
+    let mut entries = dir.dirs.iter().map(|&d| format!("dir {}", dirs[d].name))
+        .chain(dir.files.iter().map(|(name, size)| format!("{size} {name}")))
+        .collect::<Vec<_>>();
+    entries.shuffle(rng);
+    input.push_str("$ ls\n");
+    for entry in entries {
+        writeln!(input, "{entry}").unwrap();
+    }
+    for &d in &dir.dirs {
+        writeln!(input, "$ cd {}", dirs[d].name).unwrap();
+        list_directory(rng, dirs, d, input);
+        input.push_str("$ cd ..\n");
+    }
+}
+
+/// Day 8: a grid of `size` x `size` tree heights.
+pub fn tree_heights(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| (0..size).map(|_| rng.gen_range('0'..='9')).chain(['\n']).collect::<String>())
+        .collect()
+}
+
+/// Day 9: `size` motions of up to 20 steps.
+pub fn rope_motions(rng: &mut impl Rng, size: usize) -> String {
+    (0..size).map(|_| format!("{} {}\n", letter(rng, "LRUD"), rng.gen_range(1..=20))).collect()
+}
+
+/// Day 10: a program of `size` instructions, or more if needed to draw the whole screen,
+/// which keeps the sprite on the screen.
+pub fn program(rng: &mut impl Rng, size: usize) -> String {
+    let mut input = String::new();
+    let (mut x, mut cycles) = (1, 0);
+    for i in 0.. {
+        if i >= size && cycles >= 240 {
+            break;
+        }
+        if rng.gen_bool(0.3) {
+            input.push_str("noop\n");
+            cycles += 1;
+        } else {
+            let next_x = (x + rng.gen_range(-10..=10)).clamp(-1, 40);
+            writeln!(input, "addx {}", next_x - x).unwrap();
+            x = next_x;
+            cycles += 2;
+        }
+    }
+    input
+}
+
+const PRIMES: [u32; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+/// Day 11: notes on `size` monkeys, between 2 and 15 so that the product of their
+/// divisors, which are different primes, fits in 64 bits. The worry levels of part 1
+/// are kept exact, so notes where they outgrow 64 bits are drawn again.
+pub fn monkey_notes(rng: &mut impl Rng, size: usize) -> String {
+    let num_monkeys = size.clamp(2, PRIMES.len());
+    loop {
+        let mut divisors = PRIMES.to_vec();
+        divisors.shuffle(rng);
+        // two monkeys would hand the squaring one its items back every round
+        let squaring = (num_monkeys > 2).then(|| rng.gen_range(0..num_monkeys));
+        let mut notes = vec![];
+        for (m, div) in divisors[..num_monkeys].iter().enumerate() {
+            let items = (0..rng.gen_range(1..=8)).map(|_| rng.gen_range(50..100).to_string()).collect::<Vec<_>>();
+            let operation = if Some(m) == squaring {
+                "old * old".to_string()
+            } else if rng.gen_bool(0.3) {
+                format!("old * {}", rng.gen_range(2..=19))
+            } else {
+                format!("old + {}", rng.gen_range(1..=8))
+            };
+            // monkeys never throw to themselves, and throw to two different ones where there are
+            let mut other_monkey = || (m + rng.gen_range(1..num_monkeys)) % num_monkeys;
+            let true_dest = other_monkey();
+            let false_dest = loop {
+                let dest = other_monkey();
+                if dest != true_dest || num_monkeys == 2 {
+                    break dest;
+                }
+            };
+            notes.push(format!(
+                "Monkey {m}:\n  Starting items: {}\n  Operation: new = {operation}\n  Test: divisible by {div}\n    \
+                 If true: throw to monkey {true_dest}\n    If false: throw to monkey {false_dest}\n",
+                items.join(", "),
+            ));
+        }
+        let notes = notes.join("\n");
+        let monkeys = day_11::parse(notes.as_bytes()).expect("generated notes parse");
+        if day_11::monkey_business_level(&monkeys, 3, 20).is_ok() {
+            return notes;
+        }
+    }
+}
+
+/// Day 12: a heightmap `size` wide and half as high, at least 8 x 5, where `E` can be
+/// reached from `S`.
+pub fn heightmap(rng: &mut impl Rng, size: usize) -> String {
+    let (width, height) = (size.max(8), (size / 2).max(5));
+    loop {
+        // a random depth-first spanning tree from S; every cell can be reached from S
+        // as long as the height grows by at most one along each edge of the tree
+        let start = Point::new(rng.gen_range(0..width as i64), rng.gen_range(0..height as i64));
+        let mut depths = Grid::new(width, height, None);
+        depths[start] = Some(0);
+        let mut path = vec![start];
+        while let Some(&pos) = path.last() {
+            let next = depths.neighbours4(pos).filter(|&p| depths[p].is_none()).collect::<Vec<_>>();
+            match next.choose(rng) {
+                Some(&p) => {
+                    depths[p] = depths[pos].map(|d| d + 1);
+                    path.push(p);
+                }
+                None => {
+                    path.pop();
+                }
+            }
+        }
+        let depths = depths.map(|d| d.unwrap());
+        let (end, &max_depth) = depths.iter().max_by_key(|&(_, d)| d).unwrap();
+        if max_depth < 25 {
+            // too short a way to climb from `a` to `z`
+            continue;
+        }
+        let mut heights = depths.map(|&d| (b'a' + (d * 25 / max_depth) as u8) as char);
+        heights[start] = 'S';
+        heights[end] = 'E';
+        return heights.to_string();
+    }
+}
+
+/// Day 13: `size` pairs of packets, nested up to 4 lists deep.
+pub fn packet_pairs(rng: &mut impl Rng, size: usize) -> String {
+    (0..size).map(|_| format!("{}\n{}\n", packet(rng, 4), packet(rng, 4))).collect::<Vec<_>>().join("\n")
+}
+
+fn packet(rng: &mut impl Rng, depth: u32) -> String {
+    let elems = (0..rng.gen_range(0..=5))
+        .map(|_| if depth > 1 && rng.gen_bool(0.3) { packet(rng, depth - 1) } else { rng.gen_range(0..=10).to_string() })
+        .collect::<Vec<_>>();
+    format!("[{}]", elems.join(","))
+}
+
+/// Day 14: `size` rock paths of up to 5 lines, at least 10 below the sand source.
+pub fn rock_paths(rng: &mut impl Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let mut p = Point::new(rng.gen_range(460..=540), rng.gen_range(10..=160));
+        let mut points = vec![p.to_string()];
+        let horizontal = rng.gen_bool(0.5);
+        for i in 0..rng.gen_range(1..=5) {
+            let length = rng.gen_range(1..=8) * if rng.gen_bool(0.5) { 1 } else { -1 };
+            if (i % 2 == 0) == horizontal {
+                p.x += length;
+            } else {
+                p.y = (p.y + length).max(10);
+            }
+            points.push(p.to_string());
+        }
+        writeln!(input, "{}", points.join(" -> ")).unwrap();
+    }
+    input
+}
+
+/// Day 15: `size` sensors in and around the puzzle's search area, each reporting the closest of
+/// `size / 2` beacons, none of them in range of the distress beacon at a random position in the area.
+pub fn sensor_reports(rng: &mut impl Rng, size: usize) -> String {
+    const AREA: i64 = 4_000_000;
+    let around = -AREA / 8..AREA * 9 / 8;
+    let beacons = (0..(size / 2).max(1)).map(|_| random_point(rng, &around)).collect::<HashSet<_>>();
+    let distress = loop {
+        let p = random_point(rng, &(0..AREA + 1));
+        if !beacons.contains(&p) {
+            break p;
+        }
+    };
+    let mut input = String::new();
+    let mut sensors = 0;
+    while sensors < size {
+        let sensor = random_point(rng, &around);
+        let mut by_distance = beacons.iter().map(|&b| (sensor.manhattan(b), b)).collect::<Vec<_>>();
+        by_distance.sort();
+        let (distance, beacon) = by_distance[0];
+        // the closest beacon has to be unambiguous
+        if distance == 0 || by_distance.get(1).is_some_and(|&(d, _)| d == distance) || sensor.manhattan(distress) <= distance {
+            continue;
+        }
+        writeln!(
+            input,
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            sensor.x, sensor.y, beacon.x, beacon.y,
+        ).unwrap();
+        sensors += 1;
+    }
+    input
+}
+
+fn random_point(rng: &mut impl Rng, range: &Range<i64>) -> Point {
+    Point::new(rng.gen_range(range.clone()), rng.gen_range(range.clone()))
+}
+
+/// Day 16: `size` valves, at least 2, all reachable from `AA` through tunnels that go both ways.
+/// One in eight valves works, but at most 15 as in the puzzle, as solving takes time
+/// exponential in their number.
+pub fn valve_scan(rng: &mut impl Rng, size: usize) -> String {
+    let num_valves = size.clamp(2, 26 * 26);
+    let mut names = (1..26 * 26).map(|i| format!("{}{}", (b'A' + (i / 26) as u8) as char, (b'A' + (i % 26) as u8) as char))
+        .collect::<Vec<_>>();
+    names.shuffle(rng);
+    names.truncate(num_valves - 1);
+    names.insert(0, "AA".to_string());
+    let mut tunnels = vec![BTreeSet::new(); num_valves];
+    // a random spanning tree keeps every valve reachable, and a few more tunnels make loops
+    let mut pairs = (1..num_valves).map(|v| (v, rng.gen_range(0..v))).collect::<Vec<_>>();
+    pairs.extend((0..num_valves / 2).map(|_| (rng.gen_range(0..num_valves), rng.gen_range(0..num_valves))));
+    for (v, w) in pairs.into_iter().filter(|(v, w)| v != w) {
+        tunnels[v].insert(w);
+        tunnels[w].insert(v);
+    }
+    let mut working = (1..num_valves).collect::<Vec<_>>();
+    working.shuffle(rng);
+    working.truncate((num_valves / 8).clamp(1, 15));
+    let mut lines = vec![];
+    for (v, next) in tunnels.iter().enumerate() {
+        let flow_rate = if working.contains(&v) { rng.gen_range(1..=25) } else { 0 };
+        let next = next.iter().map(|&w| names[w].as_str()).collect::<Vec<_>>();
+        let tunnels = if next.len() == 1 { "tunnel leads to valve" } else { "tunnels lead to valves" };
+        lines.push(format!("Valve {} has flow rate={flow_rate}; {tunnels} {}\n", names[v], next.join(", ")));
+    }
+    lines.shuffle(rng);
+    lines.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::registry;
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_input() {
+        for day in 1..=16 {
+            assert_eq!(generate(day, 7, 10).unwrap(), generate(day, 7, 10).unwrap());
+        }
+        assert_ne!(generate(11, 1, 10).unwrap(), generate(11, 2, 10).unwrap());
+        assert!(generate(17, 1, 10).is_err());
+    }
+
+    #[test]
+    fn generated_inputs_are_valid_and_solvable() {
+        for (&day, solution) in &registry() {
+            // days added by `aoc new` have no generator until one is written
+            if matches!(generate(day, 0, 1), Err(Error { kind: ErrorKind::NoSuchPuzzle(_, _), .. })) {
+                continue;
+            }
+            for seed in 0..4 {
+                let text = generate(day, seed, 12).unwrap();
+                let problems = solution.check(&text);
+                assert!(problems.is_empty(), "day {day} seed {seed}: {}\n{text}", problems[0]);
+                let input = solution.parse(&text).unwrap();
+                for part in 1..=2 {
+                    if let Err(e) = solution.part(part, input.as_ref()).unwrap() {
+                        panic!("day {day} part {part} seed {seed}: {e}\n{text}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A directed graph, given by the edges leaving each node.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbours(&self, node: &Self::Node) -> impl Iterator<Item = Self::Node>;
+
+    /// Cost of the edge from `from` to its neighbour `to`; 1 unless the graph is weighted.
+    fn cost(&self, _from: &Self::Node, _to: &Self::Node) -> u64 {
+        1
+    }
+}
+
+/// Adjacency lists, with nodes numbered by their index.
+impl Graph for Vec<Vec<usize>> {
+    type Node = usize;
+
+    fn neighbours(&self, node: &usize) -> impl Iterator<Item = usize> {
+        self[*node].iter().copied()
+    }
+}
+
+/// Adjacency lists keyed by node. Nodes without an entry have no outgoing edges.
+impl<N: Clone + Eq + Hash> Graph for HashMap<N, Vec<N>> {
+    type Node = N;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = N> {
+        self.get(node).into_iter().flatten().cloned()
+    }
+}
+
+/// The result of a search from a start node: the distances of the nodes reached,
+/// and the edge each of them was first reached by.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    distances: HashMap<N, u64>,
+    predecessors: HashMap<N, N>,
+    /// the first node reached that satisfied the goal, if any
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new(start: N) -> Self {
+        Search { distances: HashMap::from([(start, 0)]), predecessors: HashMap::new(), goal: None }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    /// Distance to the goal, if one was reached.
+    pub fn goal_distance(&self) -> Option<u64> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    /// All nodes reached, with their distances.
+    pub fn distances(&self) -> &HashMap<N, u64> {
+        &self.distances
+    }
+
+    /// The shortest path from the start to `node`, both included; `None` if it was not reached.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.contains_key(node).then(|| {
+            let mut path = vec![node.clone()];
+            while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            path
+        })
+    }
+}
+
+/// Breadth-first search from `start`, ignoring edge costs.
+/// Stops at the first node for which `is_goal` holds; pass `|_| false` to reach every node.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node, mut is_goal: impl FnMut(&G::Node) -> bool) -> Search<G::Node> {
+    let mut search = Search::new(start.clone());
+    if is_goal(&start) {
+        search.goal = Some(start);
+        return search;
+    }
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node];
+        for next in graph.neighbours(&node) {
+            if search.distances.contains_key(&next) {
+                continue;
+            }
+            search.distances.insert(next.clone(), distance + 1);
+            search.predecessors.insert(next.clone(), node.clone());
+            if is_goal(&next) {
+                search.goal = Some(next);
+                return search;
+            }
+            queue.push_back(next);
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm from `start`. Stops once the cheapest node satisfying `is_goal` is settled.
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node, is_goal: impl FnMut(&G::Node) -> bool) -> Search<G::Node> {
+    astar(graph, start, is_goal, |_| 0)
+}
+
+/// A* search from `start`, guided by `heuristic`, which must never overestimate
+/// the remaining cost to a goal for the result to be a shortest path.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    mut heuristic: impl FnMut(&G::Node) -> u64,
+) -> Search<G::Node> {
+    let mut search = Search::new(start.clone());
+    // the heap holds indices into `queued`, so that nodes need not be ordered
+    let mut queued = vec![(start.clone(), 0)];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    while let Some(Reverse((_, i))) = heap.pop() {
+        let (node, distance) = queued[i].clone();
+        if search.distances[&node] < distance {
+            // a shorter way was found after this entry was queued
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            return search;
+        }
+        for next in graph.neighbours(&node) {
+            let next_distance = distance + graph.cost(&node, &next);
+            if search.distance(&next).is_some_and(|d| d <= next_distance) {
+                continue;
+            }
+            search.distances.insert(next.clone(), next_distance);
+            search.predecessors.insert(next.clone(), node.clone());
+            heap.push(Reverse((next_distance + heuristic(&next), queued.len())));
+            queued.push((next, next_distance));
+        }
+    }
+    search
+}
+
+/// Shortest distances between all pairs of nodes, from the Floyd–Warshall algorithm.
+#[derive(Debug, Clone)]
+pub struct AllPairs<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    distances: Vec<Option<u64>>,
+}
+
+impl<N: Clone + Eq + Hash> AllPairs<N> {
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// `None` if `to` cannot be reached from `from`, or either is not a node of the graph.
+    pub fn distance(&self, from: &N, to: &N) -> Option<u64> {
+        let (&i, &j) = (self.index.get(from)?, self.index.get(to)?);
+        self.distances[i * self.nodes.len() + j]
+    }
+
+    /// The graph restricted to `keep`, with an edge between every pair of them that is
+    /// connected in the original graph, costing the length of the shortest path.
+    /// Useful to skip over nodes that only matter as a way through.
+    pub fn compress(&self, keep: &[N]) -> Compressed<N> {
+        let mut edges = HashMap::new();
+        for from in keep {
+            let reachable = keep.iter()
+                .filter(|&to| to != from)
+                .filter_map(|to| Some((to.clone(), self.distance(from, to)?)))
+                .collect();
+            edges.insert(from.clone(), reachable);
+        }
+        Compressed { edges }
+    }
+}
+
+/// Runs Floyd–Warshall over `nodes`, which must include every node reachable from them.
+pub fn floyd_warshall<G: Graph>(graph: &G, nodes: impl IntoIterator<Item = G::Node>) -> AllPairs<G::Node> {
+    let nodes = nodes.into_iter().collect::<Vec<_>>();
+    let index = nodes.iter().cloned().enumerate().map(|(i, n)| (n, i)).collect::<HashMap<_, _>>();
+    let n = nodes.len();
+    let mut distances = vec![None; n * n];
+    for (i, node) in nodes.iter().enumerate() {
+        distances[i * n + i] = Some(0);
+        for next in graph.neighbours(node) {
+            let j = *index.get(&next).expect("every neighbour is among the nodes");
+            let cost = graph.cost(node, &next);
+            if distances[i * n + j].is_none_or(|d| cost < d) {
+                distances[i * n + j] = Some(cost);
+            }
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            let Some(ik) = distances[i * n + k] else { continue };
+            for j in 0..n {
+                let Some(kj) = distances[k * n + j] else { continue };
+                if distances[i * n + j].is_none_or(|d| ik + kj < d) {
+                    distances[i * n + j] = Some(ik + kj);
+                }
+            }
+        }
+    }
+    AllPairs { nodes, index, distances }
+}
+
+/// A weighted graph with explicit edge costs, e.g. from [`AllPairs::compress`].
+#[derive(Debug, Clone)]
+pub struct Compressed<N> {
+    edges: HashMap<N, HashMap<N, u64>>,
+}
+
+impl<N: Clone + Eq + Hash> Compressed<N> {
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    /// The cost of going from `from` to `to`, or `None` if `to` cannot be reached from `from`.
+    pub fn distance(&self, from: &N, to: &N) -> Option<u64> {
+        self.edges.get(from)?.get(to).copied()
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph for Compressed<N> {
+    type Node = N;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = N> {
+        self.edges.get(node).into_iter().flat_map(HashMap::keys).cloned()
+    }
+
+    fn cost(&self, from: &N, to: &N) -> u64 {
+        self.edges[from][to]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3, plus a shortcut 0 -> 2; node 4 is unreachable
+    fn line() -> Vec<Vec<usize>> {
+        vec![vec![1, 2], vec![2], vec![3], vec![], vec![0]]
+    }
+
+    #[test]
+    fn bfs_distances_and_paths() {
+        let search = bfs(&line(), 0, |&n| n == 3);
+        assert_eq!(search.goal_distance(), Some(2));
+        assert_eq!(search.path_to(&3), Some(vec![0, 2, 3]));
+        assert_eq!(search.path_to(&4), None);
+        assert_eq!(bfs(&line(), 0, |_| false).distances().len(), 4);
+    }
+
+    #[test]
+    fn weighted_searches() {
+        let pairs = floyd_warshall(&line(), 0..5);
+        let graph = pairs.compress(&[0, 1, 3]);
+        // 0 -> 3 keeps the cost of the path through the dropped node 2
+        assert_eq!(graph.cost(&0, &3), 2);
+        assert_eq!((graph.distance(&0, &3), graph.distance(&3, &0)), (Some(2), None));
+        let search = dijkstra(&graph, 0, |&n| n == 3);
+        assert_eq!((search.goal_distance(), search.path_to(&3)), (Some(2), Some(vec![0, 3])));
+        let search = astar(&graph, 1, |&n| n == 3, |&n| if n == 3 { 0 } else { 1 });
+        assert_eq!(search.goal_distance(), Some(2));
+        assert_eq!(dijkstra(&graph, 3, |&n| n == 0).goal, None);
+    }
+
+    #[test]
+    fn all_pairs() {
+        let pairs = floyd_warshall(&line(), 0..5);
+        assert_eq!(pairs.distance(&4, &3), Some(3));
+        assert_eq!(pairs.distance(&3, &0), None);
+        assert_eq!(pairs.distance(&0, &5), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::ops::{Index, IndexMut, Range};
+
+use crate::check::check_lines;
+use crate::error::{Error, Result};
+use crate::point::{Dir, Dir8, Point};
+
+/// Rectangular grid of cells, stored row by row.
+///
+/// The top left cell is at `(x_min, y_min)`, which is `(0, 0)` unless the grid was
+/// created with [`Grid::with_bounds`], so coordinates can be negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    x_min: i64,
+    y_min: i64,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self where T: Clone {
+        Grid { cells: vec![fill; width * height], width, height, x_min: 0, y_min: 0 }
+    }
+
+    /// A grid covering the given coordinate ranges.
+    pub fn with_bounds(x_range: Range<i64>, y_range: Range<i64>, fill: T) -> Self where T: Clone {
+        let width = x_range.end.saturating_sub(x_range.start).max(0) as usize;
+        let height = y_range.end.saturating_sub(y_range.start).max(0) as usize;
+        Grid { x_min: x_range.start, y_min: y_range.start, ..Grid::new(width, height, fill) }
+    }
+
+    /// Builds a grid from equally long rows; `None` if they are not.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid { cells: rows.into_iter().flatten().collect(), width, height, x_min: 0, y_min: 0 })
+    }
+
+    /// Parses one row per input line, converting characters with `cell`.
+    /// A character `cell` rejects is reported as not being `what`.
+    pub fn parse(input: impl BufRead, mut cell: impl FnMut(char) -> Option<T>, what: &str) -> Result<Self> {
+        let mut rows: Vec<Vec<T>> = vec![];
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            let width = rows.first().map(Vec::len);
+            rows.push(parse_row(i + 1, &line, width, &mut cell, what)?);
+        }
+        if rows.first().is_none_or(Vec::is_empty) {
+            return Err(empty_grid(what));
+        }
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    /// Every problem [`Grid::parse`] finds in `input`, rather than only the first.
+    pub fn check(input: &str, mut cell: impl FnMut(char) -> Option<T>, what: &str) -> Vec<Error> {
+        let width = input.lines().next().map(|line| line.chars().count());
+        let mut problems = check_lines(input, |i, line| {
+            parse_row(i, line, width.filter(|_| i > 1), &mut cell, what).map(drop)
+        });
+        if width.is_none_or(|width| width == 0) {
+            problems.push(empty_grid(what));
+        }
+        problems
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn x_range(&self) -> Range<i64> {
+        self.x_min..self.x_min + self.width as i64
+    }
+
+    pub fn y_range(&self) -> Range<i64> {
+        self.y_min..self.y_min + self.height as i64
+    }
+
+    pub fn contains(&self, pos: Point) -> bool {
+        self.x_range().contains(&pos.x) && self.y_range().contains(&pos.y)
+    }
+
+    /// Row-major index of the cell at `pos`, e.g. to number the nodes of a graph.
+    pub fn index_of(&self, pos: Point) -> Option<usize> {
+        self.contains(pos).then(|| {
+            (pos.y - self.y_min) as usize * self.width + (pos.x - self.x_min) as usize
+        })
+    }
+
+    /// Inverse of [`Grid::index_of`].
+    pub fn pos_of(&self, index: usize) -> Point {
+        Point::new(self.x_min + (index % self.width) as i64, self.y_min + (index / self.width) as i64)
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Sets the cell at `pos`; returns whether it is in the grid.
+    pub fn set(&mut self, pos: Point, value: T) -> bool {
+        self.get_mut(pos).map(|cell| *cell = value).is_some()
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|i| self.pos_of(i))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: i64) -> impl Iterator<Item = &T> {
+        let y = y - self.y_min;
+        let row = if (0..self.height as i64).contains(&y) {
+            &self.cells[y as usize * self.width..(y as usize + 1) * self.width]
+        } else {
+            &[]
+        };
+        row.iter()
+    }
+
+    pub fn column(&self, x: i64) -> impl Iterator<Item = &T> {
+        let x = x - self.x_min;
+        let skip = if (0..self.width as i64).contains(&x) { x as usize } else { self.cells.len() };
+        self.cells.iter().skip(skip).step_by(self.width.max(1))
+    }
+
+    /// Positions within the grid in the given direction, starting next to `pos`;
+    /// none for a zero step, which would never leave the grid.
+    pub fn ray(&self, pos: Point, step: Point) -> impl Iterator<Item = (Point, &T)> {
+        (1..)
+            .take_while(move |_| step != Point::new(0, 0))
+            .map(move |i| pos + step * i)
+            .map_while(|p| self.get(p).map(|cell| (p, cell)))
+    }
+
+    /// The up to 4 orthogonally adjacent positions within the grid.
+    pub fn neighbours4(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        Dir::ALL.into_iter()
+            .map(move |dir| pos + dir.offset())
+            .filter(|&p| self.contains(p))
+    }
+
+    /// The up to 8 orthogonally or diagonally adjacent positions within the grid.
+    pub fn neighbours8(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        Dir8::ALL.into_iter()
+            .map(move |dir| pos + dir.offset())
+            .filter(|&p| self.contains(p))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+            x_min: self.x_min,
+            y_min: self.y_min,
+        }
+    }
+}
+
+// a row of the given width, if known
+fn parse_row<T>(line_no: usize, line: &str, width: Option<usize>, mut cell: impl FnMut(char) -> Option<T>, what: &str) -> Result<Vec<T>> {
+    let mut row = vec![];
+    for (j, c) in line.chars().enumerate() {
+        row.push(cell(c).ok_or_else(|| Error::expected(what).at(line_no, j + 1, line))?);
+    }
+    match width {
+        Some(width) if row.len() != width => {
+            Err(Error::expected(format!("a row of width {width}")).at(line_no, 1, line))
+        }
+        _ => Ok(row),
+    }
+}
+
+fn empty_grid(what: &str) -> Error {
+    Error::expected(format!("a grid of {what}"))
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Point) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{pos} is outside the grid {:?} x {:?}", self.x_range(), self.y_range()))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, pos: Point) -> &mut T {
+        let (x_range, y_range) = (self.x_range(), self.y_range());
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos} is outside the grid {x_range:?} x {y_range:?}"))
+    }
+}
+
+/// Renders the grid one row per line, with the cells' own `Display`.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in self.y_range() {
+            for cell in self.row(y) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point {
+        Point::new(x, y)
+    }
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n".as_bytes(), |c| c.to_digit(10), "digits").unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[p(2, 1)], 6);
+        assert_eq!(grid.get(p(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let e = Grid::parse("12\n4x\n".as_bytes(), |c| c.to_digit(10), "digits").unwrap_err();
+        assert_eq!(e.to_string(), "2:2: expected digits");
+        let e = Grid::parse("12\n456\n".as_bytes(), |c| c.to_digit(10), "digits").unwrap_err();
+        assert_eq!(e.to_string(), "2:1: expected a row of width 2");
+        let problems = Grid::check("12\n4x\n456\n", |c| c.to_digit(10), "digits");
+        assert_eq!(problems.iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+            "2:2: expected digits",
+            "3:1: expected a row of width 2",
+        ]);
+    }
+
+    #[test]
+    fn rows_columns_and_rays() {
+        let grid = digits();
+        assert_eq!(grid.row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.ray(p(0, 0), Dir::Right.offset()).map(|(_, &c)| c).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(grid.ray(p(0, 0), p(1, 1)).map(|(p, _)| p).collect::<Vec<_>>(), vec![p(1, 1)]);
+        assert_eq!(grid.ray(p(0, 0), p(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = digits();
+        assert_eq!(grid.neighbours4(p(0, 0)).collect::<Vec<_>>(), vec![p(1, 0), p(0, 1)]);
+        assert_eq!(grid.neighbours8(p(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn negative_offsets() {
+        let mut grid = Grid::with_bounds(-2..1, -1..1, '.');
+        assert!(grid.set(p(-2, -1), '#'));
+        assert!(!grid.set(p(1, 0), '#'));
+        assert_eq!(grid.index_of(p(-2, -1)), Some(0));
+        assert_eq!(grid.pos_of(4), p(-1, 0));
+        assert_eq!(grid.to_string(), "#..\n...\n");
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::Range;
+
+use crate::point::Coord;
+
+/// A set of numbers, stored as sorted, disjoint and non-adjacent half-open ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet<T = i64> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Coord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    /// The ranges making up the set, in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Adds `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    // index of the first range ending after `x`
+    fn after(&self, x: T) -> usize {
+        self.ranges.partition_point(|r| r.end <= x)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.ranges.get(self.after(x)).is_some_and(|r| r.start <= x)
+    }
+
+    /// Whether all of `range` is in the set; true for an empty range.
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        range.is_empty() || self.ranges.get(self.after(range.start))
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Whether any of `range` is in the set.
+    pub fn overlaps(&self, range: &Range<T>) -> bool {
+        !range.is_empty() && self.ranges.get(self.after(range.start)).is_some_and(|r| r.start < range.end)
+    }
+
+    /// How many numbers the set holds.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.start.distance(r.end)).sum()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.ranges.iter().cloned());
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let common = a.start.max(b.start)..a.end.min(b.end);
+            if !common.is_empty() {
+                ranges.push(common);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        IntervalSet { ranges: self.ranges.iter().flat_map(|r| other.gaps(r.clone())).collect() }
+    }
+
+    /// The parts of `within` that are not in the set, in ascending order.
+    pub fn gaps(&self, within: Range<T>) -> Vec<Range<T>> {
+        let mut gaps = vec![];
+        let mut start = within.start;
+        for r in &self.ranges[self.after(within.start)..] {
+            if r.start >= within.end {
+                break;
+            }
+            if r.start > start {
+                gaps.push(start..r.start);
+            }
+            start = r.end;
+        }
+        if start < within.end {
+            gaps.push(start..within.end);
+        }
+        gaps
+    }
+}
+
+impl<T: Coord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Coord> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, ranges: I) {
+        for range in ranges {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Coord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(ranges);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn insert_merges() {
+        let s = set(&[5..8, 0..2, 2..3, 7..10, 12..12, -4..-1]);
+        assert_eq!(s.ranges(), &[-4..-1, 0..3, 5..10]);
+        assert_eq!(s.len(), 11);
+        assert_eq!(set(&[0..2, 4..6, 1..5]), IntervalSet::from(0..6));
+    }
+
+    #[test]
+    fn queries() {
+        let s = set(&[0..3, 5..10]);
+        assert!(s.contains(0) && s.contains(9) && !s.contains(3) && !s.contains(10));
+        assert!(s.contains_range(&(6..10)) && !s.contains_range(&(2..6)) && s.contains_range(&(4..4)));
+        assert!(s.overlaps(&(2..6)) && !s.overlaps(&(3..5)) && !s.overlaps(&(1..1)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[0..3, 5..10]);
+        let b = set(&[2..6, 8..12]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..12));
+        assert_eq!(a.intersection(&b), set(&[2..3, 5..6, 8..10]));
+        assert_eq!(a.difference(&b), set(&[0..2, 6..8]));
+        assert_eq!(a.gaps(-2..20), vec![-2..0, 3..5, 10..20]);
+        assert_eq!(a.gaps(6..9), vec![]);
+    }
+}
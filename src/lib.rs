@@ -1,36 +1,53 @@
+//! Advent of Code 2022 solutions, usable as a library.
+//!
+//! Each `day_NN` module exports the day's puzzle model, its parser (`parse` or a
+//! similarly named function reading the puzzle input line by line) and the solvers
+//! for both parts, plus a `DayNN` type implementing [`Solution`] for running it
+//! through the [`registry`]. The shared building blocks live in [`grid`], [`point`],
+//! [`graph`] and [`intervals`].
+
 extern crate core;
 
 #[allow(dead_code)]
 mod demo;
-#[allow(dead_code)]
-mod day_01;
-#[allow(dead_code)]
-mod day_02;
-#[allow(dead_code)]
-mod day_03;
-#[allow(dead_code)]
-mod day_04;
-#[allow(dead_code)]
-mod day_05;
-#[allow(dead_code)]
-mod day_06;
-#[allow(dead_code)]
-mod day_07;
-#[allow(dead_code)]
-mod day_08;
-#[allow(dead_code)]
-mod day_09;
-#[allow(dead_code)]
-mod day_10;
-#[allow(dead_code)]
-mod day_11;
-#[allow(dead_code)]
-mod day_12;
-#[allow(dead_code)]
-mod day_13;
-#[allow(dead_code)]
-mod day_14;
-#[allow(dead_code)]
-mod day_15;
-#[allow(dead_code)]
-mod day_16;
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+
+pub mod animate;
+pub mod bench;
+pub mod check;
+pub mod error;
+#[cfg(test)]
+mod examples;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod intervals;
+pub mod json;
+pub mod point;
+pub mod render;
+pub mod runner;
+pub mod scaffold;
+#[cfg(test)]
+mod snapshots;
+pub mod solution;
+pub mod trace;
+pub mod verify;
+pub mod watch;
+
+pub use error::{Error, ErrorKind, Result};
+pub use solution::{registry, Answer, DynSolution, Registry, Solution};
@@ -0,0 +1,485 @@
+use std::io::ErrorKind::NotFound;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use adventofcode_2022::animate::{self, Settings};
+use adventofcode_2022::bench::{self, Baseline};
+use adventofcode_2022::check;
+use adventofcode_2022::generate;
+use adventofcode_2022::error::{Error, ErrorKind};
+use adventofcode_2022::json::Json;
+use adventofcode_2022::render::{self, Palette};
+use adventofcode_2022::runner::{self, Report};
+use adventofcode_2022::scaffold;
+use adventofcode_2022::trace::{self, Filter};
+use adventofcode_2022::verify::{self, Answers, Outcome};
+use adventofcode_2022::watch;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print the solutions' events to stderr, e.g. `day11=debug,day16=trace`, or `info` for every day
+    #[arg(long, global = true)]
+    trace: Option<Filter>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    /// One object per part, with the answer, timings and any error's fields
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one part of a day's puzzle and print the answer
+    Run {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Puzzle input file, or `-` for stdin [default: input-NN.txt]
+        #[arg(long)]
+        input: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Set a puzzle parameter instead of its default, e.g. `row=10` for day 15's example; repeatable
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+        /// Also print why the answer is what it is, such as the route taken, for the days that can tell
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Run only the parser of a day's puzzle and report every malformed line of the input
+    Check {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Puzzle input file, or `-` for stdin [default: input-NN.txt]
+        #[arg(long)]
+        input: Option<String>,
+    },
+    /// Print a random puzzle input for a day, e.g. to stress-test its solution
+    Generate {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Random seed; the same seed gives the same input [default: random, printed on stderr]
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Roughly how many items the input describes, e.g. elves, monkeys or valves
+        #[arg(long, default_value_t = generate::DEFAULT_SIZE)]
+        size: usize,
+    },
+    /// Draw a picture of a day's puzzle, e.g. the cave filled with sand on day 14
+    Render {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Puzzle input file, or `-` for stdin [default: input-NN.txt]
+        #[arg(long)]
+        input: Option<String>,
+        /// What to draw, e.g. `visible` or `scenic` on day 8 [default: the day's first view]
+        #[arg(long)]
+        view: Option<String>,
+        /// Colors for views that show numbers: heat, terrain, gray or spectrum [default: per view]
+        #[arg(long)]
+        palette: Option<Palette>,
+        /// Size of each cell in pixels
+        #[arg(long, default_value_t = 4)]
+        scale: usize,
+        /// Image file; `.ppm`, `.png` or `.svg` chooses the format
+        #[arg(long)]
+        output: String,
+        /// Set a puzzle parameter instead of its default, e.g. `source=498,0` for day 14; repeatable
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+    /// Play a day's simulation in the terminal: the rope on day 9, the CRT on day 10, sand on day 14
+    Animate {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Simulate this part's variant, e.g. 10 knots or the cave floor
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Puzzle input file, or `-` for stdin [default: input-NN.txt]
+        #[arg(long)]
+        input: Option<String>,
+        /// Frames per second, or 0 for as fast as possible
+        #[arg(long, default_value_t = Settings::default().fps)]
+        fps: f64,
+        /// Simulation steps between frames
+        #[arg(long, default_value_t = Settings::default().steps_per_frame)]
+        step: usize,
+        /// Viewport width in characters
+        #[arg(long, default_value_t = Settings::default().width)]
+        width: usize,
+        /// Viewport height in characters
+        #[arg(long, default_value_t = Settings::default().height)]
+        height: usize,
+        /// Set a puzzle parameter instead of its default, e.g. `screen_width=20` for day 10; repeatable
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+    /// Solve both parts of a day again whenever its input or example changes, showing what changed
+    Watch {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Input file to watch; repeatable [default: input-NN.txt and example-NN.txt]
+        #[arg(long)]
+        input: Vec<String>,
+        /// Set a puzzle parameter instead of its default, e.g. `row=10` for day 15's example; repeatable
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+        /// How often to look for changes, in milliseconds
+        #[arg(long, default_value_t = watch::DEFAULT_INTERVAL.as_millis() as u64)]
+        interval: u64,
+    },
+    /// Add a day to the crate in the current directory: a skeleton module, registered,
+    /// with an ignored example test and empty input files
+    New {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Solve both parts of every day in parallel and print a summary
+    All {
+        /// Only solve this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Number of worker threads [default: number of CPUs]
+        #[arg(long)]
+        threads: Option<NonZeroUsize>,
+        /// `json` prints an array of the parts' results instead of the table
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Check every solution's answers for its default input against the recorded ones
+    Verify {
+        /// Only check this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Recorded answers file
+        #[arg(long, default_value = verify::DEFAULT_ANSWERS_PATH)]
+        answers: String,
+        /// Record the answers that are missing from the answers file
+        #[arg(long)]
+        record: bool,
+    },
+    /// Time parsing and each part of every day's solution on its default input
+    Bench {
+        /// Only time this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Number of runs of each phase
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// Compare the medians against this baseline file
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Median slowdown against the baseline, in percent, that counts as a regression
+        #[arg(long, default_value_t = 20.0)]
+        threshold: f64,
+        /// Save the medians as the new baseline
+        #[arg(long, num_args = 0..=1, default_missing_value = bench::DEFAULT_BASELINE_PATH)]
+        save: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(filter) = &cli.trace {
+        trace::set_filter(filter);
+    }
+    match cli.command {
+        Command::Run { day, part, input, format: Format::Json, params, explain } => {
+            let input = input.unwrap_or_else(|| runner::default_input_path(day));
+            let report = runner::report(day, part, &input, &params, explain);
+            println!("{}", report.to_json());
+            match report.outcome {
+                runner::Outcome::Answer(_) => ExitCode::SUCCESS,
+                runner::Outcome::Error(Error { kind: ErrorKind::NoSuchPuzzle(_, _), .. }) => ExitCode::from(2),
+                _ => ExitCode::FAILURE,
+            }
+        }
+        Command::Run { day, part, input, format: Format::Text, params, explain } => {
+            let input = input.unwrap_or_else(|| runner::default_input_path(day));
+            let solved = if explain {
+                runner::explain(day, part, &input, &params)
+            } else {
+                runner::run(day, part, &input, &params).map(|answer| (answer, None))
+            };
+            match solved {
+                Ok((answer, witness)) => {
+                    println!("{answer}");
+                    match witness {
+                        Some(witness) => println!("    {}", witness.replace('\n', "\n    ")),
+                        None if explain => eprintln!("day {day} part {part} gives no witness"),
+                        None => {}
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    match e.kind {
+                        ErrorKind::NoSuchPuzzle(_, _) => ExitCode::from(2),
+                        _ => ExitCode::FAILURE,
+                    }
+                }
+            }
+        }
+        Command::Check { day, input } => {
+            let input = input.unwrap_or_else(|| runner::default_input_path(day));
+            check_input(day, &input)
+        }
+        Command::Generate { day, seed, size } => {
+            let seed = seed.unwrap_or_else(|| {
+                let seed = rand::random();
+                eprintln!("seed: {seed}");
+                seed
+            });
+            match generate::generate(day, seed, size) {
+                Ok(input) => {
+                    print!("{input}");
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::from(2)
+                }
+            }
+        }
+        Command::Render { day, input, view, palette, scale, output, params } => {
+            let input = input.unwrap_or_else(|| runner::default_input_path(day));
+            let rendered = render::render(day, view.as_deref(), &input, palette.as_ref(), &params)
+                .and_then(|image| render::save(&image, &output, scale).map(|_| image));
+            match rendered {
+                Ok(image) => {
+                    println!("{output}: {}x{} cells", image.width(), image.height());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Animate { day, part, input, fps, step, width, height, params } => {
+            let input = input.unwrap_or_else(|| runner::default_input_path(day));
+            let settings = Settings { fps, steps_per_frame: step, width, height };
+            let animated = animate::simulation(day, part, &input, &params)
+                .and_then(|mut sim| animate::animate(sim.as_mut(), &settings, &mut std::io::stdout().lock()));
+            match animated {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Watch { day, input, params, interval } => {
+            let paths = if input.is_empty() { watch::default_paths(day) } else { input };
+            let interval = Duration::from_millis(interval);
+            match watch::watch(day, &paths, &params, interval, &mut std::io::stdout().lock()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    match e.kind {
+                        ErrorKind::NoSuchPuzzle(_, _) => ExitCode::from(2),
+                        _ => ExitCode::FAILURE,
+                    }
+                }
+            }
+        }
+        Command::New { day } => match scaffold::new_day(Path::new("."), day) {
+            Ok(written) => {
+                for path in written {
+                    println!("wrote {}", path.display());
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        Command::All { day, threads, format } => {
+            let threads = threads.or_else(|| thread::available_parallelism().ok());
+            run_all(day, threads.map_or(1, NonZeroUsize::get), format)
+        }
+        Command::Verify { day, answers: answers_path, record } => {
+            let answers = match Answers::load(&answers_path) {
+                Ok(answers) => answers,
+                // there is nothing to compare against yet, but we can start recording
+                Err(e) if record && is_not_found(&e) => Answers::default(),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            verify_answers(answers, &answers_path, day, record)
+        }
+        Command::Bench { day, iterations, baseline, threshold, save } => {
+            run_bench(day, iterations, baseline, threshold, save)
+        }
+    }
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected NAME=VALUE, not `{s}`")),
+    }
+}
+
+fn check_input(day: u8, input: &str) -> ExitCode {
+    let problems = match check::check(day, input) {
+        Ok(problems) => problems,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return match e.kind {
+                ErrorKind::NoSuchPuzzle(_, _) => ExitCode::from(2),
+                _ => ExitCode::FAILURE,
+            };
+        }
+    };
+    for e in &problems {
+        println!("error: {e}");
+        if let Some(snippet) = e.snippet() {
+            println!("{snippet}");
+        }
+    }
+    match problems.len() {
+        0 => {
+            println!("{input}: ok");
+            ExitCode::SUCCESS
+        }
+        n => {
+            println!("{input}: {n} problems");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_all(day: Option<u8>, threads: usize, format: Format) -> ExitCode {
+    let reports = runner::run_all(day, threads, |r| {
+        let status = match r.outcome {
+            runner::Outcome::Answer(_) => "done",
+            runner::Outcome::Error(_) => "failed",
+            runner::Outcome::Panic(_) => "panicked",
+        };
+        eprintln!("day {:02} part {} {status} in {:.1?}", r.day, r.part, r.elapsed());
+    });
+    let failed = reports.iter().filter(|r| !matches!(r.outcome, runner::Outcome::Answer(_))).count();
+    if format == Format::Json {
+        println!("{}", Json::Array(reports.iter().map(Report::to_json).collect()));
+        return if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+    }
+    println!("day part {:>10}  answer", "time");
+    for r in &reports {
+        print!("{:3} {:4} {:>10.1?}  ", r.day, r.part, r.elapsed());
+        match &r.outcome {
+            // multi-line answers start on a line of their own
+            runner::Outcome::Answer(answer) => println!("{}", answer.to_string().replace('\n', "\n    ")),
+            runner::Outcome::Error(e) => println!("error: {e}"),
+            runner::Outcome::Panic(message) => println!("panicked: {message}"),
+        }
+    }
+    let total = reports.iter().map(|r| r.elapsed()).sum::<Duration>();
+    println!("{} parts, {failed} failed, {total:.1?} of solving", reports.len());
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn run_bench(day: Option<u8>, iterations: usize, baseline: Option<String>, threshold: f64, save: Option<String>) -> ExitCode {
+    let load = |path: &str| Baseline::load(path).map_err(|e| eprintln!("error: {e}"));
+    let Ok(baseline) = baseline.as_deref().map(load).transpose() else {
+        return ExitCode::FAILURE;
+    };
+    let timings = match bench::bench(day, iterations) {
+        Ok(timings) => timings,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut regressions = 0;
+    for t in &timings {
+        print!("day {:02} {:5}  min {:>10.1?}  median {:>10.1?}  max {:>10.1?}", t.day, t.phase, t.min(), t.median(), t.max());
+        if let Some(change) = baseline.as_ref().and_then(|b| b.change(t)) {
+            print!("  {:+6.1}%", change * 100.0);
+            if change * 100.0 > threshold {
+                regressions += 1;
+                print!("  REGRESSION");
+            }
+        }
+        println!();
+    }
+    if let Some(path) = save {
+        // keep the other days when only some were timed
+        let mut new_baseline = match Baseline::load(&path) {
+            Ok(baseline) => baseline,
+            Err(e) if is_not_found(&e) => Baseline::default(),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+        new_baseline.update(&timings);
+        if let Err(e) = new_baseline.save(&path) {
+            eprintln!("error: {path}: {e}");
+            return ExitCode::FAILURE;
+        }
+        println!("saved baseline in {path}");
+    }
+    if regressions > 0 {
+        println!("{regressions} regressions over {threshold}%");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn is_not_found(e: &Error) -> bool {
+    matches!(&e.kind, ErrorKind::IO(io) if io.kind() == NotFound)
+}
+
+fn verify_answers(mut answers: Answers, answers_path: &str, day: Option<u8>, record: bool) -> ExitCode {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for check in verify::verify(&answers, day) {
+        let name = format!("day {:02} part {} ({})", check.day, check.part, check.input);
+        match check.outcome {
+            Outcome::Pass => {
+                passed += 1;
+                println!("{name}: ok");
+            }
+            Outcome::Fail { expected, actual } => {
+                failed += 1;
+                println!("{name}: FAILED");
+                println!("{}", verify::line_diff(&expected, &actual, None));
+            }
+            Outcome::Missing(actual) => {
+                missing += 1;
+                println!("{name}: missing, got {actual}");
+                if record {
+                    answers.insert(check.day, check.part, &check.input, actual);
+                }
+            }
+            Outcome::Error(e) => {
+                failed += 1;
+                println!("{name}: FAILED: {e}");
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if record && missing > 0 {
+        if let Err(e) = answers.save(answers_path) {
+            eprintln!("error: {answers_path}: {e}");
+            return ExitCode::FAILURE;
+        }
+        println!("recorded {missing} answers in {answers_path}");
+    }
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
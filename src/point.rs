@@ -0,0 +1,291 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, RangeInclusive, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Numeric types usable as point coordinates.
+pub trait Coord: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    /// Absolute difference, widened so that it cannot overflow for signed types.
+    fn distance(self, other: Self) -> u64;
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            fn distance(self, other: Self) -> u64 {
+                self.abs_diff(other) as u64
+            }
+        })*
+    };
+}
+
+coord!(i32, i64, isize, u32, u64, usize);
+
+/// A point, or the vector between two points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub fn manhattan(self, other: Self) -> u64 {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    pub fn chebyshev(self, other: Self) -> u64 {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// Whether the points touch, including diagonally or by overlapping.
+    pub fn touches(self, other: Self) -> bool {
+        self.chebyshev(other) <= 1
+    }
+}
+
+macro_rules! signed_point {
+    ($($t:ty),*) => {
+        $(impl Point<$t> {
+            /// The vector with each component reduced to -1, 0 or 1,
+            /// i.e. a single step towards the direction of `self`.
+            pub fn signum(self) -> Self {
+                Point { x: self.x.signum(), y: self.y.signum() }
+            }
+        })*
+    };
+}
+
+signed_point!(i32, i64, isize);
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point { x: -self.x, y: -self.y }
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Point { x: self.x * k, y: self.y * k }
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// Reads `x,y`, as written by `Display`.
+impl<T: FromStr> FromStr for Point<T> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let no_point = || Error::expected("a point `X,Y`");
+        let (x, y) = s.split_once(',').ok_or_else(no_point)?;
+        let coord = |c: &str| c.trim().parse::<T>().map_err(|_| no_point());
+        Ok(Point { x: coord(x)?, y: coord(y)? })
+    }
+}
+
+/// The four orthogonal directions, with `y` growing downwards as in the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    /// Clockwise, starting from `Up`.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    pub fn offset<T: From<i8>>(self) -> Point<T> {
+        let (x, y) = match self {
+            Dir::Up => (0, -1),
+            Dir::Right => (1, 0),
+            Dir::Down => (0, 1),
+            Dir::Left => (-1, 0),
+        };
+        Point { x: T::from(x), y: T::from(y) }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Dir::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Dir::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Dir::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// The eight compass directions, with north pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    /// Clockwise, starting from `N`.
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    pub fn offset<T: From<i8>>(self) -> Point<T> {
+        let (x, y) = match self {
+            Dir8::N => (0, -1),
+            Dir8::NE => (1, -1),
+            Dir8::E => (1, 0),
+            Dir8::SE => (1, 1),
+            Dir8::S => (0, 1),
+            Dir8::SW => (-1, 1),
+            Dir8::W => (-1, 0),
+            Dir8::NW => (-1, -1),
+        };
+        Point { x: T::from(x), y: T::from(y) }
+    }
+
+    /// Rotates clockwise by `eighths` of a full turn.
+    pub fn rotate(self, eighths: i32) -> Self {
+        Dir8::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+/// Smallest axis-aligned rectangle containing a set of points, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox<T = i64> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Coord> BoundingBox<T> {
+    pub fn new(p: Point<T>) -> Self {
+        BoundingBox { min: p, max: p }
+    }
+
+    /// `None` if there are no points.
+    pub fn of(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bbox = BoundingBox::new(points.next()?);
+        for p in points {
+            bbox.include(p);
+        }
+        Some(bbox)
+    }
+
+    /// Grows the box to contain `p`.
+    pub fn include(&mut self, p: Point<T>) {
+        self.min = Point { x: self.min.x.min(p.x), y: self.min.y.min(p.y) };
+        self.max = Point { x: self.max.x.max(p.x), y: self.max.y.max(p.y) };
+    }
+
+    pub fn contains(&self, p: Point<T>) -> bool {
+        self.x_range().contains(&p.x) && self.y_range().contains(&p.y)
+    }
+
+    pub fn x_range(&self) -> RangeInclusive<T> {
+        self.min.x..=self.max.x
+    }
+
+    pub fn y_range(&self) -> RangeInclusive<T> {
+        self.min.y..=self.max.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_distances() {
+        let p: Point = Point::new(1, -2);
+        let q = Point::new(4, 2);
+        assert_eq!(p + q, Point::new(5, 0));
+        assert_eq!(q - p, Point::new(3, 4));
+        assert_eq!((q - p) * 2, Point::new(6, 8));
+        assert_eq!((p - q).signum(), Point::new(-1, -1));
+        assert_eq!(p.manhattan(q), 7);
+        assert_eq!(p.chebyshev(q), 4);
+        assert!(!p.touches(q));
+        assert!(p.touches(p + Point::new(1, 1)));
+    }
+
+    #[test]
+    fn rotation() {
+        assert_eq!(Dir::Up.turn_right(), Dir::Right);
+        assert_eq!(Dir::Up.turn_left(), Dir::Left);
+        assert_eq!(Dir::Left.reverse(), Dir::Right);
+        assert_eq!(Dir::Down.offset::<i64>(), Point::new(0, 1));
+        assert_eq!(Dir8::N.rotate(-1), Dir8::NW);
+        assert_eq!(Dir8::from(Dir::Left).rotate(3), Dir8::NE);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bbox = BoundingBox::of([Point::new(3, 1), Point::new(-1, 5), Point::new(2, 2)]).unwrap();
+        assert_eq!((bbox.min, bbox.max), (Point::new(-1, 1), Point::new(3, 5)));
+        assert!(bbox.contains(Point::new(0, 3)));
+        assert!(!bbox.contains(Point::new(0, 6)));
+        assert_eq!(BoundingBox::<i64>::of([]), None);
+    }
+}
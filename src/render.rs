@@ -0,0 +1,387 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+
+use crate::day_14::map::{FieldContent, Map};
+use crate::day_15::SensorBeacon;
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::point::{BoundingBox, Point};
+use crate::solution::{no_params, read_input, with_params};
+use crate::{day_08, day_09, day_12, day_14, day_15};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const RED: Color = Color::rgb(230, 40, 40);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    /// The color `t` of the way from `self` to `other`, for `t` from 0 to 1.
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::rgb(channel(self.r, other.r), channel(self.g, other.g), channel(self.b, other.b))
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// A gradient through evenly spaced colors, to show numbers as colors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    pub const NAMES: [&str; 4] = ["heat", "terrain", "gray", "spectrum"];
+
+    /// `None` without any colors.
+    pub fn new(colors: Vec<Color>) -> Option<Self> {
+        (!colors.is_empty()).then_some(Palette { colors })
+    }
+
+    pub fn heat() -> Self {
+        Palette { colors: vec![Color::rgb(0, 0, 0), Color::rgb(180, 20, 20), Color::rgb(250, 200, 30), Color::WHITE] }
+    }
+
+    pub fn terrain() -> Self {
+        Palette { colors: vec![Color::rgb(20, 90, 40), Color::rgb(120, 170, 60), Color::rgb(140, 100, 60), Color::WHITE] }
+    }
+
+    pub fn gray() -> Self {
+        Palette { colors: vec![Color::BLACK, Color::WHITE] }
+    }
+
+    pub fn spectrum() -> Self {
+        let hues = [(230, 60, 60), (240, 180, 40), (90, 200, 70), (40, 190, 200), (70, 90, 230), (200, 70, 210)];
+        Palette { colors: hues.iter().map(|&(r, g, b)| Color::rgb(r, g, b)).collect() }
+    }
+
+    /// The color `t` of the way along the gradient; `t` is clamped to 0..=1.
+    pub fn at(&self, t: f64) -> Color {
+        let x = t.clamp(0.0, 1.0) * (self.colors.len() - 1) as f64;
+        let i = (x.floor() as usize).min(self.colors.len() - 1);
+        match self.colors.get(i + 1) {
+            Some(&next) => self.colors[i].mix(next, x - i as f64),
+            None => self.colors[i],
+        }
+    }
+
+    /// Well separated colors for telling the `i`-th of many things apart.
+    pub fn distinct(&self, i: usize) -> Color {
+        // steps by the golden ratio never land close to an earlier one
+        self.at((i as f64 * 0.618_033_988_75).fract())
+    }
+}
+
+impl FromStr for Palette {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "heat" => Ok(Palette::heat()),
+            "terrain" => Ok(Palette::terrain()),
+            "gray" => Ok(Palette::gray()),
+            "spectrum" => Ok(Palette::spectrum()),
+            _ => Err(Error::expected(format!("a palette: {}", Palette::NAMES.join(", ")))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl Format {
+    /// The format named by the extension of `path`.
+    pub fn of_path(path: &str) -> Result<Format> {
+        match path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+            Some("ppm") => Ok(Format::Ppm),
+            Some("png") => Ok(Format::Png),
+            Some("svg") => Ok(Format::Svg),
+            _ => Err(Error::expected("a `.ppm`, `.png` or `.svg` file name").in_input(path)),
+        }
+    }
+}
+
+/// Encodes an image, drawing each cell as a square of `scale` x `scale` pixels.
+pub fn encode(image: &Grid<Color>, format: Format, scale: usize) -> Result<Vec<u8>> {
+    let scale = scale.max(1);
+    let (width, height) = (image.width() * scale, image.height() * scale);
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    if format != Format::Svg {
+        for y in image.y_range() {
+            let row = image.row(y)
+                .flat_map(|c| [c.r, c.g, c.b].repeat(scale))
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                rgb.extend(&row);
+            }
+        }
+    }
+    match format {
+        Format::Ppm => {
+            let mut bytes = format!("P6\n{width} {height}\n255\n").into_bytes();
+            bytes.extend(rgb);
+            Ok(bytes)
+        }
+        Format::Png => {
+            let mut bytes = vec![];
+            let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.write_header()
+                .and_then(|mut writer| writer.write_image_data(&rgb))
+                .map_err(io::Error::from)?;
+            Ok(bytes)
+        }
+        Format::Svg => Ok(svg(image, scale).into_bytes()),
+    }
+}
+
+// one rectangle per run of equally colored cells in a row
+fn svg(image: &Grid<Color>, scale: usize) -> String {
+    let (width, height) = (image.width(), image.height());
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {width} {height}\" \
+         shape-rendering=\"crispEdges\">\n",
+        width * scale, height * scale,
+    );
+    for (y, row_y) in image.y_range().enumerate() {
+        let row = image.row(row_y).collect::<Vec<_>>();
+        let mut x = 0;
+        for run in row.chunk_by(|a, b| a == b) {
+            writeln!(svg, "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"1\" fill=\"{}\"/>", run.len(), run[0].hex()).unwrap();
+            x += run.len();
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes an image in the format named by the extension of `path`.
+pub fn save(image: &Grid<Color>, path: &str, scale: usize) -> Result<()> {
+    let bytes = encode(image, Format::of_path(path)?, scale)?;
+    fs::write(path, bytes).map_err(|e| Error::from(e).in_input(path))
+}
+
+/// The views each day can be rendered as; the first one is the default.
+pub const VIEWS: [(u8, &[&str]); 5] = [
+    (8, &["visible", "scenic"]),
+    (9, &["tail", "tail10"]),
+    (12, &["path"]),
+    (14, &["cave", "floor"]),
+    (15, &["coverage"]),
+];
+
+pub fn views(day: u8) -> Option<&'static [&'static str]> {
+    VIEWS.iter().find(|&&(d, _)| d == day).map(|&(_, views)| views)
+}
+
+/// Renders `view`, or the default view of `day`, from the input at `input_path`.
+/// Views that show numbers as colors use `palette`, or a palette chosen for the view.
+/// `params` set the puzzle's parameters, such as day 14's sand source, as for [`crate::runner::run`].
+pub fn render(
+    day: u8,
+    view: Option<&str>,
+    input_path: &str,
+    palette: Option<&Palette>,
+    params: &[(String, String)],
+) -> Result<Grid<Color>> {
+    let views = views(day).ok_or_else(|| {
+        let days = VIEWS.iter().map(|(d, _)| d.to_string()).collect::<Vec<_>>();
+        Error::expected(format!("a day with images: {}", days.join(", ")))
+    })?;
+    let view = match view {
+        None => views[0],
+        Some(view) if views.contains(&view) => view,
+        Some(_) => return Err(Error::expected(format!("a view of day {day}: {}", views.join(", ")))),
+    };
+    // only the sand depends on the puzzle's parameters
+    let sand = match day {
+        14 => with_params(day_14::Day14::default(), day, params)?,
+        _ => no_params(day, params).map(|_| day_14::Day14::default())?,
+    };
+    let located = |e: Error| e.in_day(day).in_input(input_path);
+    let input = read_input(input_path).map_err(|e| located(e.into()))?;
+    let input = input.as_bytes();
+    let palette = |default: fn() -> Palette| palette.cloned().unwrap_or_else(default);
+    let image = match view {
+        "visible" => day_08::read(input).map(|trees| visible_trees(&trees, &palette(Palette::terrain))),
+        "scenic" => day_08::read(input).map(|trees| scenic_heatmap(&trees, &palette(Palette::heat))),
+        "tail" | "tail10" => day_09::parse(input).map(|motions| {
+            let knots = if view == "tail" { 2 } else { 10 };
+            tail_trail(&day_09::tail_visits(&motions, knots))
+        }),
+        "path" => {
+            let heights = day_12::read_chars(input).map_err(located)?;
+            let terrain = day_12::parse_graph(heights.to_string().as_bytes()).map_err(located)?;
+            day_12::path_start_end(&terrain).map(|path| {
+                let path = path.into_iter().map(|node| heights.pos_of(node)).collect::<Vec<_>>();
+                heightmap_path(&heights, &path, &palette(Palette::terrain))
+            })
+        }
+        "cave" | "floor" => day_14::parse::parse_points(input).and_then(|paths| {
+            let source = sand.source;
+            let mut map = if view == "cave" {
+                day_14::parse::map(&paths, source)?
+            } else {
+                day_14::parse::map_with_floor(&paths, source)?
+            };
+            day_14::pour_sand(&mut map, source)?;
+            Ok(cave(&map))
+        }),
+        "coverage" => day_15::parse(input).and_then(|sensors| coverage(&sensors, &palette(Palette::spectrum))),
+        _ => unreachable!("every view is rendered"),
+    };
+    image.map_err(located)
+}
+
+/// Visible trees in the colors of their heights, hidden ones darkened.
+pub fn visible_trees(trees: &Grid<u8>, palette: &Palette) -> Grid<Color> {
+    let visible = day_08::visibility(trees);
+    let mut image = trees.map(|&h| palette.at(h as f64 / 9.0));
+    for (pos, &v) in visible.iter() {
+        if !v {
+            image[pos] = image[pos].mix(Color::BLACK, 0.8);
+        }
+    }
+    image
+}
+
+/// Scenic scores on a square root scale, so that the few best trees do not wash out the rest.
+pub fn scenic_heatmap(trees: &Grid<u8>, palette: &Palette) -> Grid<Color> {
+    let scores = day_08::scenic_scores(trees);
+    let max = scores.iter().map(|(_, &s)| s).max().unwrap_or(0).max(1) as f64;
+    scores.map(|&s| palette.at((s as f64 / max).sqrt()))
+}
+
+/// The positions visited by the tail, with the start in red.
+pub fn tail_trail(visits: &std::collections::HashSet<Point>) -> Grid<Color> {
+    let bbox = BoundingBox::of(visits.iter().copied()).unwrap_or(BoundingBox::new(Point::default()));
+    let mut image = Grid::with_bounds(bbox.min.x..bbox.max.x + 1, bbox.min.y..bbox.max.y + 1, Color::BLACK);
+    for &p in visits {
+        image[p] = Color::WHITE;
+    }
+    image.set(Point::default(), Color::RED);
+    image
+}
+
+/// The heightmap in the colors of the heights, with the path and its ends drawn over it.
+pub fn heightmap_path(heights: &Grid<char>, path: &[Point], palette: &Palette) -> Grid<Color> {
+    let level = |c: char| match c {
+        'S' => 0,
+        'E' => 25,
+        c => c as u8 - b'a',
+    };
+    let mut image = heights.map(|&c| palette.at(level(c) as f64 / 25.0));
+    for &p in path {
+        image[p] = Color::RED;
+    }
+    for (&&end, color) in path.first().iter().chain(path.last().iter()).zip([Color::WHITE, Color::BLACK]) {
+        image[end] = color;
+    }
+    image
+}
+
+/// Rock and resting sand.
+pub fn cave(map: &Map) -> Grid<Color> {
+    map.grid().map(|field| match field {
+        FieldContent::Empty => Color::rgb(25, 20, 30),
+        FieldContent::Rock => Color::rgb(120, 120, 130),
+        FieldContent::RestingSand => Color::rgb(230, 190, 100),
+    })
+}
+
+/// Number of cells along the longer side of a [`coverage`] image.
+pub const COVERAGE_RESOLUTION: i64 = 200;
+
+/// The area in range of each sensor in its own color, sampled at the center of each cell,
+/// with the sensors in white and the beacons in black.
+pub fn coverage(sensors: &[SensorBeacon], palette: &Palette) -> Result<Grid<Color>> {
+    let reach = |s: &SensorBeacon| s.sensor.manhattan(s.closest_beacon) as i64;
+    let bbox = BoundingBox::of(sensors.iter().flat_map(|s| {
+        let r = Point::new(reach(s), reach(s));
+        [s.sensor - r, s.sensor + r]
+    })).ok_or_else(|| Error::expected("at least one sensor"))?;
+    let cell = (bbox.max.x - bbox.min.x).max(bbox.max.y - bbox.min.y) / COVERAGE_RESOLUTION + 1;
+    let cell_of = |p: Point| Point::new((p.x - bbox.min.x) / cell, (p.y - bbox.min.y) / cell);
+    let size = cell_of(bbox.max) + Point::new(1, 1);
+    let mut image = Grid::new(size.x as usize, size.y as usize, Color::BLACK);
+    for pos in image.positions().collect::<Vec<_>>() {
+        let center = bbox.min + pos * cell + Point::new(cell / 2, cell / 2);
+        if let Some(i) = sensors.iter().position(|s| s.sensor.manhattan(center) as i64 <= reach(s)) {
+            image[pos] = palette.distinct(i).mix(Color::BLACK, 0.3);
+        }
+    }
+    for s in sensors {
+        image[cell_of(s.sensor)] = Color::WHITE;
+        image[cell_of(s.closest_beacon)] = Color::BLACK;
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_gradient() {
+        let gray = Palette::gray();
+        assert_eq!(gray.at(0.0), Color::BLACK);
+        assert_eq!(gray.at(2.0), Color::WHITE);
+        assert_eq!(gray.at(0.5), Color::rgb(128, 128, 128));
+        assert_eq!(Palette::heat().at(1.0 / 3.0), Color::rgb(180, 20, 20));
+        assert!("plaid".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn encodings() {
+        let image = Grid::from_rows(vec![vec![Color::RED, Color::RED, Color::WHITE]]).unwrap();
+        let ppm = encode(&image, Format::Ppm, 2).unwrap();
+        assert!(ppm.starts_with(b"P6\n6 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 2 * 3);
+        let svg = String::from_utf8(encode(&image, Format::Svg, 2).unwrap()).unwrap();
+        assert!(svg.contains("width=\"6\" height=\"2\" viewBox=\"0 0 3 1\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"2\" height=\"1\" fill=\"#e62828\"/>"));
+        assert!(encode(&image, Format::Png, 1).unwrap().starts_with(b"\x89PNG"));
+        assert!(Format::of_path("out.gif").is_err());
+    }
+
+    #[test]
+    fn views_of_the_examples() {
+        let trees = render(8, None, "example-08.txt", Some(&Palette::gray()), &[]).unwrap();
+        assert_eq!((trees.width(), trees.height()), (5, 5));
+        // the edges are visible, the tree in the middle is hidden
+        assert_eq!(trees[Point::new(0, 0)], Palette::gray().at(3.0 / 9.0));
+        assert_eq!(trees[Point::new(2, 2)], Palette::gray().at(3.0 / 9.0).mix(Color::BLACK, 0.8));
+        let path = render(12, Some("path"), "example-12.txt", None, &[]).unwrap();
+        assert_eq!(path.iter().filter(|&(_, &c)| c == Color::RED).count(), 31 + 1 - 2);
+        assert!(render(14, Some("scenic"), "example-14.txt", None, &[]).is_err());
+        assert!(render(1, None, "example-01.txt", None, &[]).is_err());
+    }
+
+    #[test]
+    fn views_take_the_puzzle_parameters() {
+        let source = [("source".to_string(), "498,0".to_string())];
+        let cave = render(14, Some("cave"), "example-14.txt", None, &source).unwrap();
+        assert_eq!(cave.iter().filter(|&(_, &c)| c == Color::rgb(230, 190, 100)).count(), 2);
+        let e = render(8, None, "example-08.txt", None, &source).unwrap_err();
+        assert_eq!(e.to_string(), "day 08: expected no parameters, not `source`");
+    }
+}
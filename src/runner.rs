@@ -0,0 +1,277 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::json::Json;
+use crate::solution::{registry, Answer, DynSolution, Registry};
+
+pub fn default_input_path(day: u8) -> String {
+    format!("input-{day:02}.txt")
+}
+
+/// Sets the named parameters of `day`'s solution, e.g. `("row", "10")` for day 15's example.
+pub fn set_params(registry: &mut Registry, day: u8, params: &[(String, String)]) -> Result<()> {
+    if let Some(solution) = registry.get_mut(&day) {
+        for (name, value) in params {
+            solution.set_param(name, value).map_err(|e| e.in_day(day))?;
+        }
+    }
+    Ok(())
+}
+
+/// Solves one part on the input at `input_path`, with the solution's parameters set to `params`.
+pub fn run(day: u8, part: u8, input_path: &str, params: &[(String, String)]) -> Result<Answer> {
+    solve(day, part, input_path, params, |solution, input| solution.part(part, input))
+}
+
+/// Solves one part like [`run`], together with the witness of the answer if the day gives one.
+pub fn explain(day: u8, part: u8, input_path: &str, params: &[(String, String)]) -> Result<(Answer, Option<String>)> {
+    solve(day, part, input_path, params, |solution, input| solution.explain(part, input))
+}
+
+fn solve<T>(
+    day: u8,
+    part: u8,
+    input_path: &str,
+    params: &[(String, String)],
+    solve: impl FnOnce(&dyn DynSolution, &dyn Any) -> Option<Result<T>>,
+) -> Result<T> {
+    let no_such_puzzle = || Error::new(ErrorKind::NoSuchPuzzle(day, part));
+    let mut registry = registry();
+    set_params(&mut registry, day, params)?;
+    let solution = registry.get(&day).ok_or_else(no_such_puzzle)?;
+    if !(1..=2).contains(&part) {
+        return Err(no_such_puzzle());
+    }
+    let input = solution.parse_file(input_path).map_err(|e| e.in_day(day))?;
+    solve(solution.as_ref(), input.as_ref())
+        .ok_or_else(no_such_puzzle)?
+        .map_err(|e| e.in_day(day).in_input(input_path))
+}
+
+/// How solving one part went in [`run_all`].
+#[derive(Debug)]
+pub enum Outcome {
+    Answer(Answer),
+    Error(Error),
+    /// the solution panicked; holds the panic message
+    Panic(String),
+}
+
+/// Result of one part in [`report`] and [`run_all`].
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    /// path of the input the part was solved for
+    pub input: String,
+    pub outcome: Outcome,
+    /// time spent parsing the input; each part parses it anew
+    pub parse_time: Duration,
+    /// time spent solving once the input is parsed
+    pub solve_time: Duration,
+    /// why the answer is what it is, when asked for and the day gives one
+    pub witness: Option<String>,
+}
+
+impl Report {
+    pub fn elapsed(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+
+    /// The report with each of its fields, for other tools to read. Answers are numbers
+    /// or strings as given by `answer_type`, and times are in nanoseconds.
+    pub fn to_json(&self) -> Json {
+        let (answer, answer_type, error) = match &self.outcome {
+            Outcome::Answer(Answer::Int(i)) => (Json::Int(*i), "int".into(), Json::Null),
+            Outcome::Answer(Answer::Text(s)) => (s.as_str().into(), "text".into(), Json::Null),
+            Outcome::Error(e) => (Json::Null, Json::Null, e.to_json()),
+            Outcome::Panic(message) => {
+                let e = Error::new(ErrorKind::Panic(message.clone())).in_day(self.day).in_input(&self.input);
+                (Json::Null, Json::Null, e.to_json())
+            }
+        };
+        let nanos = |d: Duration| Json::Int(i64::try_from(d.as_nanos()).unwrap_or(i64::MAX));
+        Json::Object(vec![
+            ("day", self.day.into()),
+            ("part", self.part.into()),
+            ("input", self.input.as_str().into()),
+            ("answer", answer),
+            ("answer_type", answer_type),
+            ("parse_ns", nanos(self.parse_time)),
+            ("solve_ns", nanos(self.solve_time)),
+            ("witness", self.witness.clone().into()),
+            ("error", error),
+        ])
+    }
+}
+
+/// Solves one part on the input at `input_path` like [`run`], but timed, and with a panic
+/// of the solution reported rather than passed on. With `explain`, the report has the
+/// answer's witness as [`explain`] gives it.
+pub fn report(day: u8, part: u8, input_path: &str, params: &[(String, String)], explain: bool) -> Report {
+    let mut registry = registry();
+    match set_params(&mut registry, day, params) {
+        Ok(()) => timed_report(&registry, day, part, input_path, explain),
+        Err(e) => Report {
+            day,
+            part,
+            input: input_path.to_string(),
+            outcome: Outcome::Error(e),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            witness: None,
+        },
+    }
+}
+
+/// Solves both parts of every registered day (or just `day`) on their default inputs,
+/// spread over `threads` threads. Each part is solved separately, so a slow part does
+/// not hold up the other one, and a panic is reported for its part only.
+///
+/// `on_done` is called as each part finishes; the reports are returned sorted by day and part.
+pub fn run_all(day: Option<u8>, threads: usize, mut on_done: impl FnMut(&Report)) -> Vec<Report> {
+    let registry = registry();
+    let jobs = registry.keys()
+        .filter(|&&d| day.is_none_or(|day| day == d))
+        .flat_map(|&d| [(d, 1), (d, 2)])
+        .collect::<Vec<_>>();
+    let next_job = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut reports = vec![];
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, jobs.len().max(1)) {
+            let sender = sender.clone();
+            let (registry, jobs, next_job) = (&registry, &jobs, &next_job);
+            scope.spawn(move || {
+                while let Some(&(day, part)) = jobs.get(next_job.fetch_add(1, Ordering::Relaxed)) {
+                    let report = timed_report(registry, day, part, &default_input_path(day), false);
+                    if sender.send(report).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // the workers hold the remaining senders, so this ends when they are done
+        drop(sender);
+        for report in receiver {
+            on_done(&report);
+            reports.push(report);
+        }
+    });
+    reports.sort_by_key(|r| (r.day, r.part));
+    reports
+}
+
+fn timed_report(registry: &Registry, day: u8, part: u8, input_path: &str, explain: bool) -> Report {
+    let (mut parse_time, mut solve_time) = (Duration::ZERO, Duration::ZERO);
+    let mut witness = None;
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        let no_such_puzzle = || Error::new(ErrorKind::NoSuchPuzzle(day, part));
+        let solution = registry.get(&day).ok_or_else(no_such_puzzle)?;
+        let start = Instant::now();
+        let input = solution.parse_file(input_path).map_err(|e| e.in_day(day));
+        parse_time = start.elapsed();
+        let (input, start) = (input?, Instant::now());
+        let answer = if explain {
+            solution.explain(part, input.as_ref()).map(|explained| {
+                explained.map(|(answer, w)| {
+                    witness = w;
+                    answer
+                })
+            })
+        } else {
+            solution.part(part, input.as_ref())
+        };
+        let answer = answer.ok_or_else(no_such_puzzle)?.map_err(|e| e.in_day(day).in_input(input_path));
+        solve_time = start.elapsed();
+        answer
+    }));
+    let outcome = match solved {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(e)) => Outcome::Error(e),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    };
+    Report { day, part, input: input_path.to_string(), outcome, parse_time, solve_time, witness }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(s), _) => s.to_string(),
+        (_, Some(s)) => s.clone(),
+        _ => "panicked".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_all_reports_sorted() {
+        let mut done = 0;
+        let reports = run_all(Some(1), 4, |_| done += 1);
+        assert_eq!(done, 2);
+        assert_eq!(reports.iter().map(|r| (r.day, r.part)).collect::<Vec<_>>(), vec![(1, 1), (1, 2)]);
+        assert!(reports.iter().all(|r| matches!(r.outcome, Outcome::Answer(_))));
+    }
+
+    #[test]
+    fn reports_as_json() {
+        let json = report(5, 1, "example-05.txt", &[], false).to_json().to_string();
+        assert!(json.starts_with(r#"{"day":5,"part":1,"input":"example-05.txt","answer":"CMZ","answer_type":"text","parse_ns":"#));
+        assert!(json.ends_with(r#","error":null}"#));
+        let drawing = report(10, 2, "example-10.txt", &[], false).to_json().to_string();
+        assert!(drawing.contains(r#""answer":"\n##  ##  ##"#));
+        let failed = report(2, 1, "example-05.txt", &[], false).to_json().to_string();
+        assert!(failed.contains(r#""answer":null,"answer_type":null"#));
+        assert!(failed.ends_with(
+            r#""error":{"kind":"expected","message":"expected two codes, e.g. `A Y`","day":2,"input":"example-05.txt","line":1,"column":12,"text":"    [D]    "}}"#
+        ));
+    }
+
+    #[test]
+    fn params_override_the_puzzle() {
+        let params = |params: &[(&str, &str)]| {
+            params.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect::<Vec<_>>()
+        };
+        let example = params(&[("row", "10"), ("max_coord", "20")]);
+        assert_eq!(run(15, 1, "example-15.txt", &example).unwrap(), Answer::Int(26));
+        assert_eq!(run(15, 2, "example-15.txt", &example).unwrap(), Answer::Int(56000011));
+        assert_eq!(run(14, 1, "example-14.txt", &params(&[("source", "498,0")])).unwrap(), Answer::Int(2));
+        let e = run(15, 1, "example-15.txt", &params(&[("rows", "10")])).unwrap_err();
+        assert_eq!(e.to_string(), "day 15: expected one of the parameters row=2000000, max_coord=4000000, not `rows`");
+        let e = run(10, 2, "example-10.txt", &params(&[("screen_width", "0")])).unwrap_err();
+        assert_eq!(e.to_string(), "day 10: expected a valid value for `screen_width`, not `0`");
+        let e = run(1, 1, "example-01.txt", &params(&[("row", "10")])).unwrap_err();
+        assert_eq!(e.to_string(), "day 01: expected no parameters, not `row`");
+        assert!(matches!(report(15, 1, "example-15.txt", &params(&[("row", "ten")]), false).outcome, Outcome::Error(_)));
+    }
+
+    #[test]
+    fn explains_answers() {
+        let witness = |day: u8, part| {
+            let (_, witness) = explain(day, part, &format!("example-{day:02}.txt"), &[]).unwrap();
+            witness
+        };
+        assert_eq!(witness(1, 2).unwrap(), "elf 4: 24000 calories\nelf 3: 11000 calories\nelf 5: 10000 calories");
+        assert_eq!(witness(7, 2).unwrap(), "delete /d of size 24933642");
+        assert_eq!(witness(8, 2).unwrap(), "the tree at 2,3, of height 5");
+        assert_eq!(witness(12, 1).unwrap(), "from 0,0: >>vvvv>>>>>^^^^<<<<vvv>>>^^<<v>");
+        assert_eq!(witness(13, 1).unwrap(), "pairs 1, 2, 4, 6");
+        assert_eq!(witness(16, 1).unwrap(), "you: DD at 2, BB at 5, JJ at 9, HH at 17, EE at 21, CC at 24");
+        assert_eq!(witness(5, 1), None);
+        let json = report(13, 2, "example-13.txt", &[], true).to_json().to_string();
+        assert!(json.contains(r#""witness":"dividers at 10 and 14""#));
+    }
+
+    #[test]
+    fn panic_messages() {
+        let payload = panic::catch_unwind(|| panic!("at {}", 42)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "at 42");
+    }
+}
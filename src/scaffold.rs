@@ -0,0 +1,196 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// The skeleton of a day's module; `__DAY__` stands for the day number and `__NN__` for
+/// the same with two digits.
+const TEMPLATE: &str = r#"//! Day __DAY__: TODO.
+
+use std::io::BufRead;
+
+use crate::check::check_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+// TODO: parse a line into the puzzle's model, locating errors with `Error::at` or `Locate`
+fn parse_line(_line_no: usize, line: &str) -> Result<String> {
+    Ok(line.to_string())
+}
+
+pub fn parse(input: impl BufRead) -> Result<Vec<String>> {
+    let mut lines = vec![];
+    for (i, line) in input.lines().enumerate() {
+        lines.push(parse_line(i + 1, &line?)?);
+    }
+    Ok(lines)
+}
+
+pub fn part1(lines: &[String]) -> Result<u64> {
+    Err(Error::no_answer(format!("part 1 is not solved yet, for {} lines", lines.len())))
+}
+
+pub fn part2(lines: &[String]) -> Result<u64> {
+    Err(Error::no_answer(format!("part 2 is not solved yet, for {} lines", lines.len())))
+}
+
+pub struct Day__NN__;
+
+impl Solution for Day__NN__ {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Result<Answer> {
+        part1(lines).map(Answer::from)
+    }
+
+    fn part2(&self, lines: &Self::Input) -> Result<Answer> {
+        part2(lines).map(Answer::from)
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        check_lines(input, |i, line| parse_line(i, line).map(drop))
+    }
+}
+
+#[cfg(test)]
+mod run {
+    use crate::solution::open_input;
+
+    use super::*;
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn print_part1() {
+        println!("{}", part1(&parse(open_input("input-__NN__.txt").unwrap()).unwrap()).unwrap());
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn print_part2() {
+        println!("{}", part2(&parse(open_input("input-__NN__.txt").unwrap()).unwrap()).unwrap());
+    }
+}
+"#;
+
+/// Adds day `day` to the crate in `root`: writes a skeleton `src/day_NN.rs`, declares it in
+/// `src/lib.rs`, adds it to the registry, adds an ignored example test, and creates empty
+/// `input-NN.txt` and `example-NN.txt` files unless they exist. Returns the files written.
+///
+/// Nothing is written if the module exists or the day is already declared anywhere.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let nn = format!("{day:02}");
+    let module = root.join(format!("src/day_{nn}.rs"));
+    if module.exists() {
+        let e = io::Error::new(io::ErrorKind::AlreadyExists, "the module already exists");
+        return Err(Error::from(e).in_input(&module.display().to_string()));
+    }
+    let edits = [
+        ("src/lib.rs", "pub mod day_", format!("pub mod day_{nn};")),
+        ("src/solution.rs", "registry.insert(", format!("registry.insert({day}, Box::new(day_{nn}::Day{nn}));")),
+        ("src/examples.rs", "day_", format!("#[ignore = \"fill in the answers\"] day_{nn}: Day{nn} => 0, 0;")),
+    ];
+    // check every edit before writing anything
+    let mut files = vec![];
+    for (path, prefix, entry) in edits {
+        let path = root.join(path);
+        let name = path.display().to_string();
+        let text = fs::read_to_string(&path).map_err(|e| Error::from(e).in_input(&name))?;
+        let text = insert_entry(&text, prefix, day, &entry).map_err(|e| e.in_input(&name))?;
+        files.push((path, text));
+    }
+    files.push((module, TEMPLATE.replace("__DAY__", &day.to_string()).replace("__NN__", &nn)));
+    for name in [format!("input-{nn}.txt"), format!("example-{nn}.txt")] {
+        let path = root.join(name);
+        if !path.exists() {
+            files.push((path, String::new()));
+        }
+    }
+    let mut written = vec![];
+    for (path, text) in files {
+        fs::write(&path, text).map_err(|e| Error::from(e).in_input(&path.display().to_string()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// The day of an entry, a line starting with `prefix` and the day number, maybe after an attribute.
+fn entry_day(line: &str, prefix: &str) -> Option<u8> {
+    let line = line.trim_start();
+    let line = match line.strip_prefix("#[") {
+        Some(attributed) => attributed.split_once("] ")?.1,
+        None => line,
+    };
+    let rest = line.strip_prefix(prefix)?;
+    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+/// Inserts `entry` among the entries for other days, keeping them in order and matching
+/// their indentation. An entry may span several lines up to one ending in `;`.
+fn insert_entry(text: &str, prefix: &str, day: u8, entry: &str) -> Result<String> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let entries = lines.iter().enumerate()
+        .filter_map(|(i, line)| entry_day(line, prefix).map(|d| (i, d)))
+        .collect::<Vec<_>>();
+    if entries.iter().any(|&(_, d)| d == day) {
+        return Err(Error::expected(format!("no entry for day {day} yet")));
+    }
+    let &(last, _) = entries.last().ok_or_else(|| Error::expected(format!("lines starting with `{prefix}NN`")))?;
+    let at = match entries.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => i,
+        None => last + lines[last..].iter().position(|line| line.trim_end().ends_with(';')).unwrap_or(0) + 1,
+    };
+    let template = lines[entries[0].0];
+    let indent = &template[..template.len() - template.trim_start().len()];
+    let mut result = lines[..at].iter().map(|line| format!("{line}\n")).collect::<String>();
+    result.push_str(&format!("{indent}{entry}\n"));
+    result.extend(lines[at..].iter().map(|line| format!("{line}\n")));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_stay_in_order() {
+        let text = "mod demo;\npub mod day_01;\npub mod day_03;\n\npub mod grid;\n";
+        assert_eq!(insert_entry(text, "pub mod day_", 2, "pub mod day_02;").unwrap(),
+                   "mod demo;\npub mod day_01;\npub mod day_02;\npub mod day_03;\n\npub mod grid;\n");
+        assert_eq!(insert_entry(text, "pub mod day_", 4, "pub mod day_04;").unwrap(),
+                   "mod demo;\npub mod day_01;\npub mod day_03;\npub mod day_04;\n\npub mod grid;\n");
+        assert!(insert_entry(text, "pub mod day_", 3, "pub mod day_03;").is_err());
+        assert_eq!(entry_day("    #[ignore = \"fill in the answers\"] day_17: Day17 => 0, 0;", "day_"), Some(17));
+        let examples = "examples! {\n    day_09: Day09 => 13, 1;\n    day_10: Day10 => 13140, concat!(\n        \"a\",\n    );\n}\n";
+        assert_eq!(insert_entry(examples, "day_", 11, "day_11: Day11 => 0, 0;").unwrap(),
+                   "examples! {\n    day_09: Day09 => 13, 1;\n    day_10: Day10 => 13140, concat!(\n        \"a\",\n    );\n    day_11: Day11 => 0, 0;\n}\n");
+    }
+
+    #[test]
+    fn scaffolds_a_day() {
+        let day = (1..=25).rev().find(|d| !crate::registry().contains_key(d)).unwrap();
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        for file in ["src/lib.rs", "src/solution.rs", "src/examples.rs"] {
+            fs::copy(file, root.join(file)).unwrap();
+        }
+        let written = new_day(&root, day).unwrap();
+        assert_eq!(written.len(), 6);
+        let read = |file: String| fs::read_to_string(root.join(file)).unwrap();
+        assert!(read("src/lib.rs".into()).contains(&format!("\npub mod day_{day:02};\n")));
+        assert!(read("src/solution.rs".into()).contains(&format!("    registry.insert({day}, Box::new(day_{day:02}::Day{day:02}));\n")));
+        assert!(read("src/examples.rs".into()).contains(&format!("    #[ignore = \"fill in the answers\"] day_{day:02}: Day{day:02} => 0, 0;\n")));
+        let module = read(format!("src/day_{day:02}.rs"));
+        assert!(module.starts_with(&format!("//! Day {day}: TODO.\n")));
+        assert!(module.contains(&format!("open_input(\"input-{day:02}.txt\")")));
+        assert_eq!(read(format!("input-{day:02}.txt")), "");
+        // a second time would clobber the module
+        assert!(new_day(&root, day).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
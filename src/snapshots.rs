@@ -0,0 +1,85 @@
+//! Snapshot tests of the text the solutions draw, such as the CRT picture of day 10 and the
+//! cave of day 14, so that a change in drawing or simulating shows up for review.
+//!
+//! Each snapshot lives in `snapshots/NAME.txt`. When a test's output differs, it is written
+//! to `snapshots/NAME.new.txt` next to it for comparison. Once the change is as intended,
+//! `UPDATE_SNAPSHOTS=1 cargo test snapshots` overwrites the snapshots with the new output.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::animate;
+use crate::day_10;
+use crate::day_14::{self, SAND_SOURCE};
+use crate::solution::open_input;
+use crate::verify::line_diff;
+
+const DIR: &str = "snapshots";
+
+/// Number of differing lines shown when a snapshot does not match.
+const SHOWN_LINES: usize = 10;
+
+fn path(name: &str, extension: &str) -> PathBuf {
+    PathBuf::from(DIR).join(format!("{name}.{extension}"))
+}
+
+/// Compares `actual` with the snapshot `name`, or makes it the snapshot in update mode.
+fn assert_snapshot(name: &str, actual: &str) {
+    let (snapshot, new) = (path(name, "txt"), path(name, "new.txt"));
+    fs::create_dir_all(DIR).unwrap();
+    if env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v != "0") {
+        fs::write(&snapshot, actual).unwrap_or_else(|e| panic!("{}: {e}", snapshot.display()));
+        // a leftover from an earlier mismatch would only confuse the review
+        let _ = fs::remove_file(&new);
+        return;
+    }
+    let expected = fs::read_to_string(&snapshot).ok();
+    if expected.as_deref() == Some(actual) {
+        let _ = fs::remove_file(&new);
+        return;
+    }
+    fs::write(&new, actual).unwrap_or_else(|e| panic!("{}: {e}", new.display()));
+    let Some(expected) = expected else {
+        panic!("no snapshot {}; review {} and run with UPDATE_SNAPSHOTS=1 to accept it", snapshot.display(), new.display());
+    };
+    panic!("{} does not match, see {}:\n{}", snapshot.display(), new.display(), line_diff(&expected, actual, Some(SHOWN_LINES)));
+}
+
+fn crt(input: &str) -> String {
+    let ops = day_10::parse(open_input(input).unwrap()).unwrap();
+    day_10::drawing(&ops, day_10::SCREEN_WIDTH, day_10::SCREEN_HEIGHT)
+}
+
+fn cave(input: &str, floor: bool) -> String {
+    let paths = day_14::parse::parse_points(open_input(input).unwrap()).unwrap();
+    let mut map = if floor {
+        day_14::parse::map_with_floor(&paths, SAND_SOURCE).unwrap()
+    } else {
+        day_14::parse::map(&paths, SAND_SOURCE).unwrap()
+    };
+    day_14::pour_sand(&mut map, SAND_SOURCE).unwrap();
+    format!("{map:?}")
+}
+
+#[test]
+fn crt_drawings() {
+    assert_snapshot("day10-example", &crt("example-10.txt"));
+    assert_snapshot("day10-input", &crt("input-10.txt"));
+}
+
+#[test]
+fn caves_full_of_sand() {
+    assert_snapshot("day14-example", &cave("example-14.txt", false));
+    assert_snapshot("day14-example-floor", &cave("example-14.txt", true));
+    assert_snapshot("day14-input", &cave("input-14.txt", false));
+}
+
+#[test]
+fn sand_falling_halfway() {
+    let mut sim = animate::simulation(14, 1, "example-14.txt", &[]).unwrap();
+    for _ in 0..100 {
+        sim.step().unwrap();
+    }
+    assert_snapshot("day14-example-step100", &animate::frame(sim.as_ref(), 20, 12));
+}
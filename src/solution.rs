@@ -0,0 +1,273 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+use crate::*;
+use crate::error::{Error, Result};
+
+/// Answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{i}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(i: $t) -> Self {
+                // an answer too large for an Int is still an answer, and prints the same as text
+                i64::try_from(i).map_or_else(|_| Answer::Text(i.to_string()), Answer::Int)
+            }
+        })*
+    };
+}
+
+answer_from_int!(u16, u32, u64, usize, i64);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// Opens a puzzle input file for line-by-line parsing.
+pub fn open_input(path: &str) -> io::Result<BufReader<File>> {
+    File::open(path).map(BufReader::new)
+}
+
+/// Reads a whole puzzle input; a path of `-` reads standard input.
+pub fn read_input(path: &str) -> io::Result<String> {
+    let mut input = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut input)?;
+    } else {
+        File::open(path)?.read_to_string(&mut input)?;
+    }
+    Ok(input)
+}
+
+/// A day's puzzle: the input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+
+    /// Every problem `parse` finds in `input`, rather than only the first.
+    /// Days whose parser can carry on past a malformed line override this.
+    fn check(&self, input: &str) -> Vec<Error> {
+        self.parse(input).err().into_iter().collect()
+    }
+
+    /// The names and values of the puzzle's parameters, such as the row to look at on day 15.
+    /// Days with parameters implement this and `set_param` with [`params!`].
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Sets a parameter from its text, e.g. when the example uses a different value than the puzzle.
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<()> {
+        Err(unknown_param(name, &self.params()))
+    }
+
+    /// Solves part 1 together with a witness of why the answer is what it is, such as the
+    /// route taken, for the days that can tell.
+    fn explain1(&self, input: &Self::Input) -> Result<(Answer, Option<String>)> {
+        Ok((self.part1(input)?, None))
+    }
+
+    /// Solves part 2 together with a witness, like [`Solution::explain1`].
+    fn explain2(&self, input: &Self::Input) -> Result<(Answer, Option<String>)> {
+        Ok((self.part2(input)?, None))
+    }
+}
+
+/// Implements [`Solution::params`] and [`Solution::set_param`] for the listed fields of the
+/// solution, which need to be `Display` and `FromStr`.
+macro_rules! params {
+    ($($field:ident),* $(,)?) => {
+        fn params(&self) -> Vec<(&'static str, String)> {
+            vec![$((stringify!($field), self.$field.to_string())),*]
+        }
+
+        fn set_param(&mut self, name: &str, value: &str) -> $crate::error::Result<()> {
+            match name {
+                $(stringify!($field) => self.$field = $crate::solution::parse_param(name, value)?,)*
+                _ => return Err($crate::solution::unknown_param(name, &self.params())),
+            }
+            Ok(())
+        }
+    };
+}
+
+pub(crate) use params;
+
+pub(crate) fn parse_param<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| Error::expected(format!("a valid value for `{name}`, not `{value}`")))
+}
+
+pub(crate) fn unknown_param(name: &str, params: &[(&'static str, String)]) -> Error {
+    if params.is_empty() {
+        return Error::expected(format!("no parameters, not `{name}`"));
+    }
+    let params = params.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>();
+    Error::expected(format!("one of the parameters {}, not `{name}`", params.join(", ")))
+}
+
+/// `solution` with the named parameters set, for the tools that solve part of a day themselves.
+pub(crate) fn with_params<S: Solution>(mut solution: S, day: u8, params: &[(String, String)]) -> Result<S> {
+    for (name, value) in params {
+        solution.set_param(name, value).map_err(|e| e.in_day(day))?;
+    }
+    Ok(solution)
+}
+
+/// Rejects parameters where the puzzle's parameters make no difference.
+pub(crate) fn no_params(day: u8, params: &[(String, String)]) -> Result<()> {
+    match params.first() {
+        Some((name, _)) => Err(unknown_param(name, &[]).in_day(day)),
+        None => Ok(()),
+    }
+}
+
+/// Object-safe view of a [`Solution`], with the input type erased,
+/// so that solutions for different days can be kept in one [`Registry`].
+pub trait DynSolution: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+
+    fn parse_file(&self, path: &str) -> Result<Box<dyn Any>> {
+        read_input(path)
+            .map_err(Error::from)
+            .and_then(|input| self.parse(&input))
+            .map_err(|e| e.in_input(path))
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer>;
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer>;
+
+    fn check(&self, input: &str) -> Vec<Error>;
+
+    fn params(&self) -> Vec<(&'static str, String)>;
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()>;
+
+    fn explain1(&self, input: &dyn Any) -> Result<(Answer, Option<String>)>;
+
+    fn explain2(&self, input: &dyn Any) -> Result<(Answer, Option<String>)>;
+
+    /// Solves part 1 or 2; `None` for any other part number.
+    fn part(&self, part: u8, input: &dyn Any) -> Option<Result<Answer>> {
+        match part {
+            1 => Some(self.part1(input)),
+            2 => Some(self.part2(input)),
+            _ => None,
+        }
+    }
+
+    /// Solves part 1 or 2 with its witness, if any; `None` for any other part number.
+    fn explain(&self, part: u8, input: &dyn Any) -> Option<Result<(Answer, Option<String>)>> {
+        match part {
+            1 => Some(self.explain1(input)),
+            2 => Some(self.explain2(input)),
+            _ => None,
+        }
+    }
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Send + Sync,
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Solution::parse(self, input).map(|input| Box::new(input) as Box<dyn Any>)
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer> {
+        Solution::part1(self, downcast::<S>(input))
+    }
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer> {
+        Solution::part2(self, downcast::<S>(input))
+    }
+
+    fn check(&self, input: &str) -> Vec<Error> {
+        Solution::check(self, input)
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        Solution::params(self)
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        Solution::set_param(self, name, value)
+    }
+
+    fn explain1(&self, input: &dyn Any) -> Result<(Answer, Option<String>)> {
+        Solution::explain1(self, downcast::<S>(input))
+    }
+
+    fn explain2(&self, input: &dyn Any) -> Result<(Answer, Option<String>)> {
+        Solution::explain2(self, downcast::<S>(input))
+    }
+}
+
+fn downcast<S>(input: &dyn Any) -> &S::Input
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    input.downcast_ref().expect("input was parsed by a different solution")
+}
+
+/// Solutions keyed by day number.
+pub type Registry = BTreeMap<u8, Box<dyn DynSolution>>;
+
+pub fn registry() -> Registry {
+    let mut registry: Registry = BTreeMap::new();
+    registry.insert(1, Box::new(day_01::Day01));
+    registry.insert(2, Box::new(day_02::Day02));
+    registry.insert(3, Box::new(day_03::Day03));
+    registry.insert(4, Box::new(day_04::Day04));
+    registry.insert(5, Box::new(day_05::Day05::default()));
+    registry.insert(6, Box::new(day_06::Day06));
+    registry.insert(7, Box::new(day_07::Day07::default()));
+    registry.insert(8, Box::new(day_08::Day08));
+    registry.insert(9, Box::new(day_09::Day09));
+    registry.insert(10, Box::new(day_10::Day10::default()));
+    registry.insert(11, Box::new(day_11::Day11));
+    registry.insert(12, Box::new(day_12::Day12));
+    registry.insert(13, Box::new(day_13::Day13));
+    registry.insert(14, Box::new(day_14::Day14::default()));
+    registry.insert(15, Box::new(day_15::Day15::default()));
+    registry.insert(16, Box::new(day_16::Day16::default()));
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_too_large_for_an_int_are_text() {
+        assert_eq!(Answer::from(42u64), Answer::Int(42));
+        assert_eq!(Answer::from(u64::MAX), Answer::Text("18446744073709551615".into()));
+        assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+    }
+}
@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+use crate::error::{Error, Result};
+
+/// How detailed an event is; enabling a level enables the less detailed ones too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info = 1,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(Error::expected("a level: info, debug or trace")),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Which events to emit: the most detailed level for each day, `None` for none at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    levels: [Option<Level>; 26],
+}
+
+impl Filter {
+    pub fn level(&self, day: u8) -> Option<Level> {
+        self.levels.get(day as usize).copied().flatten()
+    }
+}
+
+/// Comma-separated directives: `day11=debug` for one day, `day11` for all of its events,
+/// and a bare level like `info` for the days without a directive of their own.
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut default = None;
+        let mut days = vec![];
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (target, level) = match directive.split_once('=') {
+                Some((target, level)) => (target, Some(level.parse()?)),
+                None if directive.starts_with("day") => (directive, Some(Level::Trace)),
+                None => ("", Some(directive.parse()?)),
+            };
+            if target.is_empty() {
+                default = level;
+                continue;
+            }
+            let day = target.strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .filter(|day| (1..=25).contains(day))
+                .ok_or_else(|| Error::expected(format!("a day from `day1` to `day25`, not `{target}`")))?;
+            days.push((day, level));
+        }
+        let mut filter = Filter { levels: [default; 26] };
+        for (day, level) in days {
+            filter.levels[day as usize] = level;
+        }
+        Ok(filter)
+    }
+}
+
+// the level of each day as set by `set_filter`, 0 for none
+static LEVELS: [AtomicU8; 26] = [const { AtomicU8::new(0) }; 26];
+
+// the events captured on this thread, and the filter enabling more of them while they are
+struct Capture {
+    events: Vec<String>,
+    filter: Option<Filter>,
+}
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+// the captures with a filter of their own on any thread, so that `enabled` only looks
+// for one on its thread while there are some
+static FILTERED_CAPTURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Enables the events passing `filter` for all threads, replacing the previous filter.
+pub fn set_filter(filter: &Filter) {
+    for (day, level) in LEVELS.iter().enumerate() {
+        level.store(filter.level(day as u8).map_or(0, |l| l as u8), Ordering::Relaxed);
+    }
+}
+
+/// Enables the events of `day` up to `level`, leaving the other days as they are.
+pub fn enable(day: u8, level: Level) {
+    if let Some(l) = LEVELS.get(day as usize) {
+        l.store(level as u8, Ordering::Relaxed);
+    }
+}
+
+/// Whether the events of `day` at `level` are enabled for all threads, or on this thread
+/// by the filter of [`capture_filtered`].
+pub fn enabled(day: u8, level: Level) -> bool {
+    LEVELS.get(day as usize).is_some_and(|l| l.load(Ordering::Relaxed) >= level as u8)
+        || FILTERED_CAPTURES.load(Ordering::Relaxed) > 0 && CAPTURED.with_borrow(|captured| {
+            captured.as_ref().and_then(|c| c.filter.as_ref()).is_some_and(|f| f.level(day) >= Some(level))
+        })
+}
+
+/// Writes an event as a line of `key=value` fields to stderr, or to the capture of [`capture`].
+/// Use [`event!`] rather than calling this, so that the fields are only formatted when enabled.
+pub fn emit(day: u8, level: Level, name: &str, fields: &[(&str, &dyn Display)]) {
+    let mut line = format!("day{day:02} {level} {name}");
+    for (key, value) in fields {
+        let value = value.to_string();
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+            write!(line, " {key}={value:?}").unwrap();
+        } else {
+            write!(line, " {key}={value}").unwrap();
+        }
+    }
+    let uncaptured = CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(capture) => {
+            capture.events.push(line);
+            None
+        }
+        None => Some(line),
+    });
+    if let Some(line) = uncaptured {
+        eprintln!("{line}");
+    }
+}
+
+/// Runs `f` and returns the events it emitted on this thread instead of writing them out.
+/// The filter still decides which events are emitted.
+pub fn capture(f: impl FnOnce()) -> Vec<String> {
+    capture_with(None, f)
+}
+
+/// Runs `f` like [`capture`], with the events passing `filter` enabled on this thread as well,
+/// so that looking at a day's events leaves the other threads alone.
+pub fn capture_filtered(filter: &Filter, f: impl FnOnce()) -> Vec<String> {
+    capture_with(Some(filter.clone()), f)
+}
+
+fn capture_with(filter: Option<Filter>, f: impl FnOnce()) -> Vec<String> {
+    // counts the filtered capture until it ends, even if `f` panics
+    struct Counted;
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            FILTERED_CAPTURES.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    let _counted = filter.is_some().then(|| {
+        FILTERED_CAPTURES.fetch_add(1, Ordering::Relaxed);
+        Counted
+    });
+    let outer = CAPTURED.replace(Some(Capture { events: vec![], filter }));
+    f();
+    CAPTURED.replace(outer).map(|capture| capture.events).unwrap_or_default()
+}
+
+/// Emits an event for a day at a level, with `key = value` fields of any `Display` type:
+///
+/// ```ignore
+/// event!(11, Debug, "throw", monkey = m, worry = wl, to = dest);
+/// ```
+macro_rules! event {
+    ($day:expr, $level:ident, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled($day, $crate::trace::Level::$level) {
+            $crate::trace::emit(
+                $day,
+                $crate::trace::Level::$level,
+                $name,
+                &[$((stringify!($key), &$value as &dyn std::fmt::Display)),*],
+            );
+        }
+    };
+}
+
+pub(crate) use event;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_directives() {
+        let filter = "info, day11=debug,day16".parse::<Filter>().unwrap();
+        assert_eq!(filter.level(1), Some(Level::Info));
+        assert_eq!(filter.level(11), Some(Level::Debug));
+        assert_eq!(filter.level(16), Some(Level::Trace));
+        assert_eq!("day7=debug".parse::<Filter>().unwrap().level(1), None);
+        assert!("day26=debug".parse::<Filter>().is_err());
+        assert!("day11=loud".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn events_of_enabled_days() {
+        // only this test enables day 25, so other tests running at the same time do not interfere
+        enable(25, Level::Debug);
+        let events = capture(|| {
+            event!(25, Debug, "throw", monkey = 1, item = "a b");
+            event!(25, Trace, "ignored", x = 0);
+        });
+        assert_eq!(events, vec!["day25 debug throw monkey=1 item=\"a b\""]);
+    }
+
+    #[test]
+    fn days_emit_structured_events() {
+        use crate::solution::open_input;
+        use crate::{day_07, day_11};
+
+        // enabled on this thread only, so that other tests solving the same days are left alone
+        let filter = "day7=debug,day11=debug".parse::<Filter>().unwrap();
+        let dirs = capture_filtered(&filter, || {
+            day_07::parse(open_input("example-07.txt").unwrap()).unwrap();
+            assert!(!std::thread::spawn(|| enabled(7, Level::Debug)).join().unwrap());
+        });
+        assert_eq!(dirs, vec![
+            "day07 debug directory path=/ size=48381165",
+            "day07 debug directory path=/a size=94853",
+            "day07 debug directory path=/a/e size=584",
+            "day07 debug directory path=/d size=24933642",
+        ]);
+        let monkeys = day_11::parse(open_input("example-11.txt").unwrap()).unwrap();
+        let events = capture_filtered(&filter, || {
+            day_11::monkey_business_level(&monkeys, 3, 20).unwrap();
+        });
+        assert_eq!(events[0], "day11 debug throw round=1 monkey=0 item=79 worry=500 to=3");
+        assert!(events.contains(&"day11 info inspections monkey=0 count=101".to_string()));
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+
+use crate::error::{Error, Locate, Result};
+use crate::runner::default_input_path;
+use crate::solution::registry;
+
+pub const DEFAULT_ANSWERS_PATH: &str = "answers.txt";
+
+/// Known-good answers, keyed by day, part and input name.
+///
+/// The file has one answer per line: `DD P INPUT ANSWER`, where line breaks in the
+/// answer are written as `\n` and backslashes as `\\`. Blank lines and `#` comments are skipped.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8, String), String>,
+}
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Answers> {
+        fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Answers::parse(&text))
+            .map_err(|e| e.in_input(path))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let expected = || Error::expected("`DAY PART INPUT ANSWER`").at(i + 1, 1, line);
+            let mut fields = line.splitn(4, ' ');
+            let mut next_field = || fields.next().filter(|f| !f.is_empty()).ok_or_else(expected);
+            let day = next_field()?;
+            let part = next_field()?;
+            let input = next_field()?;
+            let answer = next_field()?;
+            let day = day.parse::<u8>().at_token(i + 1, line, day)?;
+            let part = part.parse::<u8>().at_token(i + 1, line, part)?;
+            answers.insert((day, part, input.to_string()), unescape(answer));
+        }
+        Ok(Answers { answers })
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.answers.get(&(day, part, input.to_string())).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, input: &str, answer: String) {
+        self.answers.insert((day, part, input.to_string()), answer);
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# DAY PART INPUT ANSWER, checked by `aoc verify`")?;
+        for ((day, part, input), answer) in &self.answers {
+            writeln!(f, "{day:02} {part} {input} {}", escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut result = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// How a solution's answer compares to the recorded one.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    /// no answer is recorded; holds the one the solution gave
+    Missing(String),
+    /// the solution failed; holds the error message
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub outcome: Outcome,
+}
+
+/// Runs every registered solution (or just the one for `day`) on its default input
+/// and compares the answers with the recorded ones.
+pub fn verify(answers: &Answers, day: Option<u8>) -> Vec<Check> {
+    let mut checks = vec![];
+    for (&d, solution) in &registry() {
+        if day.is_some_and(|day| day != d) {
+            continue;
+        }
+        let input_path = default_input_path(d);
+        let input = solution.parse_file(&input_path);
+        for part in 1..=2 {
+            let answer = input.as_ref()
+                .map_err(|e| e.to_string())
+                .and_then(|input| match solution.part(part, input.as_ref()) {
+                    Some(answer) => answer.map_err(|e| e.in_input(&input_path).to_string()),
+                    None => Err(format!("no solution for day {d} part {part}")),
+                });
+            let outcome = match (answer, answers.get(d, part, &input_path)) {
+                (Err(e), _) => Outcome::Error(e),
+                (Ok(actual), None) => Outcome::Missing(actual.to_string()),
+                (Ok(actual), Some(expected)) if actual.to_string() == expected => Outcome::Pass,
+                (Ok(actual), Some(expected)) => Outcome::Fail {
+                    expected: expected.to_string(),
+                    actual: actual.to_string(),
+                },
+            };
+            checks.push(Check { day: d, part, input: input_path.clone(), outcome });
+        }
+    }
+    checks
+}
+
+/// The lines that differ between `old` and `new`, numbered from 1, with `-` for the old line
+/// and `+` for the new one; with `max_lines`, at most that many of each, and then a count of the rest.
+pub fn line_diff(old: &str, new: &str, max_lines: Option<usize>) -> String {
+    let (old, new) = (old.lines().collect::<Vec<_>>(), new.lines().collect::<Vec<_>>());
+    let mut diff = vec![];
+    for i in 0..old.len().max(new.len()) {
+        let (o, n) = (old.get(i), new.get(i));
+        if o != n {
+            diff.extend(o.map(|line| format!("{:4} - {line}", i + 1)));
+            diff.extend(n.map(|line| format!("{:4} + {line}", i + 1)));
+        }
+    }
+    let shown = max_lines.map_or(diff.len(), |max_lines| diff.len().min(2 * max_lines));
+    let mut result = diff[..shown].join("\n");
+    if shown < diff.len() {
+        result.push_str(&format!("\n     ... and {} more", diff.len() - shown));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::default();
+        answers.insert(1, 1, "input-01.txt", "69281".to_string());
+        answers.insert(10, 2, "input-10.txt", "\n# #\n\\o/".to_string());
+        let text = answers.to_string();
+        assert!(text.contains("10 2 input-10.txt \\n# #\\n\\\\o/\n"));
+        assert_eq!(Answers::parse(&text).unwrap(), answers);
+    }
+
+    #[test]
+    fn malformed_line() {
+        let e = Answers::parse("01 1 input-01.txt 1\n02 x input-02.txt 2\n").unwrap_err();
+        assert_eq!(e.to_string(), "2:4: expected a number (invalid digit found in string)");
+    }
+
+    #[test]
+    fn diffs_show_the_changed_lines() {
+        assert_eq!(line_diff("a\nb\nc\n", "a\nx\nc\nd\n", Some(10)), "   2 - b\n   2 + x\n   4 + d");
+        let many = line_diff(&"a\n".repeat(12), &"b\n".repeat(12), Some(10));
+        assert!(many.ends_with("  10 + b\n     ... and 4 more"));
+        assert!(line_diff(&"a\n".repeat(12), &"b\n".repeat(12), None).ends_with("  12 - a\n  12 + b"));
+    }
+}
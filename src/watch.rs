@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::runner::{self, Outcome, Report};
+use crate::solution::registry;
+use crate::verify::line_diff;
+
+/// How often [`watch`] looks at the files by default.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Number of differing lines [`line_diff`] shows of a changed multi-line answer.
+const SHOWN_LINES: usize = 10;
+
+/// The files watched for a day unless told otherwise: its input and its example.
+pub fn default_paths(day: u8) -> Vec<String> {
+    vec![runner::default_input_path(day), format!("example-{day:02}.txt")]
+}
+
+// when a file last changed, as far as can be told without reading it; `None` while it is missing
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &str) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Notices changes to files by polling their modification times and sizes, which needs
+/// nothing from the platform and is quick enough for a handful of files.
+pub struct Watcher {
+    files: Vec<(String, Stamp)>,
+}
+
+impl Watcher {
+    /// Watches `paths`; the first call to [`Watcher::changed`] reports those that exist.
+    pub fn new(paths: &[String]) -> Self {
+        Watcher { files: paths.iter().map(|path| (path.clone(), None)).collect() }
+    }
+
+    /// The files changed or created since the last call; removed files are not reported.
+    pub fn changed(&mut self) -> Vec<String> {
+        let mut changed = vec![];
+        for (path, last) in &mut self.files {
+            let now = stamp(path);
+            if now != *last {
+                if now.is_some() {
+                    changed.push(path.clone());
+                }
+                *last = now;
+            }
+        }
+        changed
+    }
+}
+
+/// Solves a day again and again, remembering the last results for each input to compare with.
+pub struct Session {
+    day: u8,
+    params: Vec<(String, String)>,
+    previous: HashMap<(String, u8), Report>,
+}
+
+impl Session {
+    pub fn new(day: u8, params: &[(String, String)]) -> Self {
+        Session { day, params: params.to_vec(), previous: HashMap::new() }
+    }
+
+    /// Solves both parts for the input at `path` and describes the answers and timings,
+    /// with how they changed since the last run on the same input.
+    pub fn rerun(&mut self, path: &str) -> String {
+        let mut text = format!("{path}\n");
+        for part in 1..=2 {
+            let report = runner::report(self.day, part, path, &self.params, false);
+            let key = (path.to_string(), part);
+            text.push_str(&describe(&report, self.previous.get(&key)));
+            self.previous.insert(key, report);
+        }
+        text
+    }
+}
+
+// one line for the part, followed by the answer or its changed lines if it has several
+fn describe(report: &Report, previous: Option<&Report>) -> String {
+    let part = report.part;
+    let answer = match &report.outcome {
+        Outcome::Answer(answer) => answer.to_string(),
+        Outcome::Error(e) => return format!("  part {part} failed: {e}\n"),
+        Outcome::Panic(message) => return format!("  part {part} panicked: {message}\n"),
+    };
+    let elapsed = report.elapsed();
+    let Some(previous) = previous else {
+        return match answer.contains('\n') {
+            false => format!("  part {part}: {answer} in {elapsed:.1?}\n"),
+            true => format!("  part {part} in {elapsed:.1?}:\n{}\n", indent(answer.trim_start_matches('\n'))),
+        };
+    };
+    let previous_answer = match &previous.outcome {
+        Outcome::Answer(answer) => Some(answer.to_string()),
+        _ => None,
+    };
+    // a failed run stops anywhere, so its time says nothing
+    let time = match previous_answer {
+        Some(_) => {
+            let change = elapsed.as_secs_f64() / previous.elapsed().as_secs_f64().max(1e-9) - 1.0;
+            format!("{elapsed:.1?}, {:+.1}%", change * 100.0)
+        }
+        None => format!("{elapsed:.1?}"),
+    };
+    match previous_answer {
+        Some(previous) if previous == answer => match answer.contains('\n') {
+            false => format!("  part {part}: {answer} in {time}, unchanged\n"),
+            true => format!("  part {part} in {time}, unchanged\n"),
+        },
+        Some(previous) if answer.contains('\n') || previous.contains('\n') => {
+            let diff = line_diff(previous.trim_start_matches('\n'), answer.trim_start_matches('\n'), Some(SHOWN_LINES));
+            format!("  part {part} in {time}, changed:\n{}\n", indent(&diff))
+        }
+        Some(previous) => format!("  part {part}: {answer} in {time}, was {previous}\n"),
+        None => format!("  part {part}: {answer} in {time}\n"),
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("    {line}")).collect::<Vec<_>>().join("\n")
+}
+
+/// Solves both parts of `day` for each of `paths` whenever it changes, looking every
+/// `interval`, and writes what it finds to `out`. Only returns on an error.
+pub fn watch(day: u8, paths: &[String], params: &[(String, String)], interval: Duration, out: &mut impl Write) -> Result<()> {
+    let mut solutions = registry();
+    if !solutions.contains_key(&day) {
+        return Err(Error::new(ErrorKind::NoSuchPuzzle(day, 1)));
+    }
+    // better to hear about a wrong parameter now than on every change
+    runner::set_params(&mut solutions, day, params)?;
+    writeln!(out, "watching {} for day {day}", paths.join(", "))?;
+    let mut watcher = Watcher::new(paths);
+    let mut session = Session::new(day, params);
+    loop {
+        let mut changed = watcher.changed();
+        if !changed.is_empty() {
+            // editors may save in several writes, so wait for the last of them
+            thread::sleep(interval);
+            for path in watcher.changed() {
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        for path in changed {
+            write!(out, "{}", session.rerun(&path))?;
+            out.flush()?;
+        }
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_changed_files() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+        fs::write(&a, "1\n").unwrap();
+        let paths = [a.display().to_string(), b.display().to_string()];
+        let mut watcher = Watcher::new(&paths);
+        assert_eq!(watcher.changed(), vec![paths[0].clone()]);
+        assert!(watcher.changed().is_empty());
+        // a different size tells even when the modification time is just as coarse
+        fs::write(&a, "12\n").unwrap();
+        fs::write(&b, "3\n").unwrap();
+        assert_eq!(watcher.changed(), paths.to_vec());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reruns_show_what_changed() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-rerun-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt").display().to_string();
+        let mut session = Session::new(1, &[]);
+        fs::write(&path, "1000\n\n2000\n\n3000\n").unwrap();
+        let first = session.rerun(&path);
+        assert!(first.starts_with(&format!("{path}\n  part 1: 3000 in ")));
+        assert!(session.rerun(&path).contains(", unchanged\n  part 2: 6000 in "));
+        fs::write(&path, "1000\n\n5000\n\n3000\n").unwrap();
+        let changed = session.rerun(&path);
+        assert!(changed.contains(", was 3000\n"), "{changed}");
+        assert!(changed.contains(", was 6000\n"), "{changed}");
+        fs::write(&path, "x\n").unwrap();
+        assert!(session.rerun(&path).contains("  part 1 failed: "));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn multi_line_answers_show_their_changed_lines() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-crt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt").display().to_string();
+        let mut session = Session::new(10, &[]);
+        let example = fs::read_to_string("example-10.txt").unwrap();
+        fs::write(&path, &example).unwrap();
+        let first = session.rerun(&path);
+        assert!(first.contains(":\n    ##  ##  ##  ##  ##  ##  ##  ##  ##  ##  \n    ###   ###"), "{first}");
+        assert!(session.rerun(&path).ends_with(", unchanged\n"));
+        // one cycle later, the sprite is drawn one pixel later from the first line on
+        fs::write(&path, format!("noop\n{example}")).unwrap();
+        let changed = session.rerun(&path);
+        assert!(changed.contains(", changed:\n       1 - ##  ##"), "{changed}");
+        assert!(changed.contains("\n       1 + ###  ##"), "{changed}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
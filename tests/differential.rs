@@ -0,0 +1,139 @@
+//! Checks optimised solvers against slow but obviously correct reference implementations
+//! on random inputs. On a mismatch, proptest shrinks the input to a minimal failing case.
+
+use std::collections::HashSet;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use adventofcode_2022::day_06::prefix_length;
+use adventofcode_2022::day_08::tree_cover;
+use adventofcode_2022::day_14::{count_resting, count_resting_with_floor, SAND_SOURCE};
+use adventofcode_2022::day_15::{count_points_with_no_beacon, SensorBeacon};
+use adventofcode_2022::grid::Grid;
+use adventofcode_2022::point::Point;
+
+// a tree is visible if, in some direction, every tree up to the edge is lower
+fn naive_tree_cover(trees: &[Vec<u8>]) -> usize {
+    let (height, width) = (trees.len(), trees[0].len());
+    let mut count = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let h = trees[y][x];
+            let visible = (0..x).all(|i| trees[y][i] < h)
+                || (x + 1..width).all(|i| trees[y][i] < h)
+                || (0..y).all(|j| trees[j][x] < h)
+                || (y + 1..height).all(|j| trees[j][x] < h);
+            if visible {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn naive_prefix_length(stream: &str, marker_length: usize) -> Option<usize> {
+    let chars = stream.chars().collect::<Vec<_>>();
+    (marker_length..=chars.len()).find(|&end| {
+        let marker = &chars[end - marker_length..end];
+        (0..marker.len()).all(|i| (i + 1..marker.len()).all(|j| marker[i] != marker[j]))
+    })
+}
+
+// drops sand one cell at a time until it falls into the abyss or, with a floor, blocks the source
+fn naive_sand(paths: &[Vec<Point>], floor: bool) -> u32 {
+    let mut blocked = HashSet::new();
+    for path in paths {
+        for segment in path.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                for y in a.y.min(b.y)..=a.y.max(b.y) {
+                    blocked.insert(Point::new(x, y));
+                }
+            }
+        }
+    }
+    let max_y = blocked.iter().map(|p| p.y).max().unwrap();
+    let mut count = 0;
+    'sand: while !blocked.contains(&SAND_SOURCE) {
+        let mut p = SAND_SOURCE;
+        loop {
+            if floor && p.y == max_y + 1 {
+                break;
+            }
+            if !floor && p.y > max_y {
+                break 'sand;
+            }
+            match [0, -1, 1].into_iter().map(|dx| p + Point::new(dx, 1)).find(|next| !blocked.contains(next)) {
+                Some(next) => p = next,
+                None => break,
+            }
+        }
+        blocked.insert(p);
+        count += 1;
+    }
+    count
+}
+
+// a cell can hold no beacon if it is in range of a sensor, and is not a known beacon
+fn naive_no_beacon(sensors: &[SensorBeacon], row: i64) -> u64 {
+    let beacons = sensors.iter().map(|s| s.closest_beacon).collect::<HashSet<_>>();
+    let reach = sensors.iter().map(|s| s.sensor.manhattan(s.closest_beacon) as i64).max().unwrap();
+    let (min_x, max_x) = sensors.iter().fold((i64::MAX, i64::MIN), |(lo, hi), s| (lo.min(s.sensor.x), hi.max(s.sensor.x)));
+    (min_x - reach..=max_x + reach)
+        .map(|x| Point::new(x, row))
+        .filter(|p| !beacons.contains(p))
+        .filter(|&p| sensors.iter().any(|s| s.sensor.manhattan(p) <= s.sensor.manhattan(s.closest_beacon)))
+        .count() as u64
+}
+
+fn tree_rows() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    (1..8usize, 1..8usize).prop_flat_map(|(width, height)| vec(vec(0..10u8, width), height))
+}
+
+// horizontal and vertical lines in turn, below the sand source
+fn rock_path() -> impl Strategy<Value = Vec<Point>> {
+    ((490..510i64, 1..12i64), any::<bool>(), vec(-4..=4i64, 1..4)).prop_map(|((x, y), horizontal, lengths)| {
+        let mut p = Point::new(x, y);
+        let mut path = vec![p];
+        for (i, length) in lengths.into_iter().enumerate() {
+            if (i % 2 == 0) == horizontal {
+                p.x += length;
+            } else {
+                p.y = (p.y + length).max(1);
+            }
+            path.push(p);
+        }
+        path
+    })
+}
+
+fn sensor() -> impl Strategy<Value = SensorBeacon> {
+    (-20..20i64, -20..20i64, -20..20i64, -20..20i64).prop_map(|(sx, sy, bx, by)| {
+        SensorBeacon { sensor: Point::new(sx, sy), closest_beacon: Point::new(bx, by) }
+    })
+}
+
+proptest! {
+    #[test]
+    fn tree_cover_matches_ray_casting(rows in tree_rows()) {
+        let trees = Grid::from_rows(rows.clone()).unwrap();
+        prop_assert_eq!(tree_cover(&trees), naive_tree_cover(&rows));
+    }
+
+    #[test]
+    fn prefix_length_matches_brute_force(stream in "[a-f]{0,40}", marker_length in 1..=6usize) {
+        prop_assert_eq!(prefix_length(&stream, marker_length).ok(), naive_prefix_length(&stream, marker_length));
+    }
+
+    #[test]
+    fn sand_matches_naive_simulation(paths in vec(rock_path(), 1..5)) {
+        prop_assert_eq!(count_resting(&paths, SAND_SOURCE).unwrap(), naive_sand(&paths, false));
+        prop_assert_eq!(count_resting_with_floor(&paths, SAND_SOURCE).unwrap(), naive_sand(&paths, true));
+    }
+
+    #[test]
+    fn no_beacon_count_matches_per_cell_check(sensors in vec(sensor(), 1..6), row in -25..25i64) {
+        prop_assert_eq!(count_points_with_no_beacon(&sensors, row), naive_no_beacon(&sensors, row));
+    }
+}
@@ -0,0 +1,50 @@
+//! Uses the library the way a downstream crate would, through its public paths only.
+
+use adventofcode_2022::day_05::stacks::{self, Move};
+use adventofcode_2022::day_13::Elem;
+use adventofcode_2022::day_14::{self, map::{AddResult, Map}};
+use adventofcode_2022::day_16;
+use adventofcode_2022::point::BoundingBox;
+use adventofcode_2022::{registry, Answer, Solution};
+
+#[test]
+fn models_and_solvers() {
+    let mut stacks = vec![vec!['A', 'B'], vec![]];
+    stacks::make_move(&mut stacks, &Move { num_crates: 1, from_stack: 0, to_stack: 1 }).unwrap();
+    assert_eq!(stacks::top_crates_str(&stacks), "AB");
+
+    assert!("[1,[2]]".parse::<Elem>().unwrap() < "[1,[3]]".parse::<Elem>().unwrap());
+
+    let paths = day_14::parse::parse_points("498,4 -> 498,6 -> 496,6\n".as_bytes()).unwrap();
+    let mut map = Map::new(BoundingBox::of(paths.concat()).unwrap());
+    assert!(map.field(paths[0][0]).is_ok());
+    // the source is outside the rock structure, so the sand falls away
+    assert!(matches!(map.add_sand(day_14::SAND_SOURCE).unwrap(), AddResult::Fall));
+
+    let input = "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+                 Valve BB has flow rate=5; tunnel leads to valve AA\n";
+    let (graph, start) = day_16::parse(input.as_bytes()).unwrap();
+    assert_eq!(graph.move_time(&start, &"BB".to_string()), Some(1));
+    assert_eq!(day_16::max_pressure(&graph, &start, 30).unwrap(), 5 * 28);
+
+    // CC and DD are cut off from the start, so CC's valve is never opened
+    let input = "Valve AA has flow rate=0; tunnels lead to valves BB
+                 Valve BB has flow rate=5; tunnel leads to valve AA
+                 Valve CC has flow rate=7; tunnel leads to valve DD
+                 Valve DD has flow rate=0; tunnel leads to valve CC
+";
+    let (graph, start) = day_16::parse(input.as_bytes()).unwrap();
+    assert_eq!(graph.move_time(&start, &"CC".to_string()), None);
+    assert_eq!(day_16::max_pressure(&graph, &start, 30).unwrap(), 5 * 28);
+    assert_eq!(day_16::max_pressure_2(&graph, &start, 2, 26).unwrap(), 5 * 24);
+}
+
+#[test]
+fn solutions_through_the_trait_and_the_registry() {
+    let input = "1000\n2000\n\n3000\n\n4000\n";
+    let day01 = adventofcode_2022::day_01::Day01;
+    let totals = Solution::parse(&day01, input).unwrap();
+    assert_eq!(day01.part1(&totals).unwrap(), Answer::Int(4000));
+    let solution = &registry()[&1];
+    assert_eq!(solution.part2(solution.parse(input).unwrap().as_ref()).unwrap(), Answer::Int(10000));
+}